@@ -0,0 +1,54 @@
+#![no_main]
+//! Feeds arbitrary frames to the decoder, which must return an error rather than panic
+//!
+//! The first byte of the input picks one of the test schemas, so that every parameter type is covered, and the second
+//! byte sets the decode options. The rest of the input is the frame.
+
+use std::sync::OnceLock;
+use libfuzzer_sys::fuzz_target;
+use packer_parser::{Options, Parser};
+
+const SCHEMAS:&[&str] = &[
+    include_str!("../../src/test_files/array_test.json"),
+    include_str!("../../src/test_files/bit_packed_test.json"),
+    include_str!("../../src/test_files/blob_test.json"),
+    include_str!("../../src/test_files/calibration_test.json"),
+    include_str!("../../src/test_files/checksum_test.json"),
+    include_str!("../../src/test_files/conditional_test.json"),
+    include_str!("../../src/test_files/const_test.json"),
+    include_str!("../../src/test_files/decimal_test.json"),
+    include_str!("../../src/test_files/endianness_test.json"),
+    include_str!("../../src/test_files/enum_test.json"),
+    include_str!("../../src/test_files/flags_test.json"),
+    include_str!("../../src/test_files/integer_test.json"),
+    include_str!("../../src/test_files/length_test.json"),
+    include_str!("../../src/test_files/limits_test.json"),
+    include_str!("../../src/test_files/multi_schema_test.json"),
+    include_str!("../../src/test_files/number_size_test.json"),
+    include_str!("../../src/test_files/object_test.json"),
+    include_str!("../../src/test_files/optional_test.json"),
+    include_str!("../../src/test_files/signal_code_test.json"),
+    include_str!("../../src/test_files/string_length_test.json"),
+    include_str!("../../src/test_files/time_test.json"),
+    include_str!("../../src/test_files/trailing_test.json"),
+    include_str!("../../src/test_files/varint_test.json"),
+    include_str!("../../src/test_files/scheme.json"),
+];
+
+static PARSERS: OnceLock<Vec<Parser>> = OnceLock::new();
+
+fuzz_target!(|data: &[u8]| {
+    let parsers = PARSERS.get_or_init(|| {
+        SCHEMAS.iter().map(|x| Parser::new_from_string(x.to_string()).unwrap()).collect()
+    });
+    let [schema, flags, frame @ ..] = data else {
+        return
+    };
+    let options = Options {
+        raw_values: flags & 1 != 0,
+        raw_unknown_enums: flags & 2 != 0,
+        lenient_constants: flags & 4 != 0,
+        check_limits: flags & 8 != 0,
+    };
+    let _ = parsers[*schema as usize % parsers.len()].decode_with_warnings(frame.to_vec(), &options);
+});
@@ -0,0 +1,131 @@
+//! Conversion of raw integer readings into engineering units
+//!
+//! Calibrations are declared on integer parameters and are applied when decoding, producing the engineering value as a
+//! number. Encoding inverts the calibration to find the raw value to send, which is possible for linear and second order
+//! polynomials as well as lookup tables that are monotonic in both columns.
+
+use serde_json::Value;
+
+use crate::Error;
+
+///Calibration curve of an integer parameter
+#[derive(Clone, Debug)]
+pub(crate) enum Calibration{
+    ///Coefficients of value = a0 + a1*raw + a2*raw^2 + ..., starting with a0
+    Polynomial(Vec<f64>),
+    ///Points of (raw, value) with strictly increasing raw values, interpolated linearly between points
+    Table{
+        points:Vec<(f64,f64)>,
+        ///Points of (value, raw) with increasing values, only present if the values are strictly monotonic
+        inverse:Option<Vec<(f64,f64)>>,
+    },
+}
+
+impl Calibration{
+    ///Compiles the calibration keyword of a parameter
+    pub(crate) fn new(definition:&Value)->Result<Calibration,Error>{
+        let definition = match definition.as_object(){
+            Some(data) => data,
+            None => return Err(Error::schema("Calibration must be a key-value map").within("calibration")),
+        };
+        if let Some(coefficients) = definition.get("coefficients"){
+            let coefficients:Option<Vec<f64>> = match coefficients.as_array(){
+                Some(data) => data.iter().map(|x| x.as_f64()).collect(),
+                None => None,
+            };
+            return match coefficients{
+                Some(data) if !data.is_empty() => Ok(Calibration::Polynomial(data)),
+                _ => Err(Error::schema("Coefficients must be a non-empty array of numbers").within("calibration")),
+            }
+        }
+        if let Some(table) = definition.get("table"){
+            let points:Option<Vec<(f64,f64)>> = match table.as_array(){
+                Some(data) => data.iter().map(|x| match x.as_array().map(|y| y.as_slice()){
+                    Some([raw,value]) => Some((raw.as_f64()?,value.as_f64()?)),
+                    _ => None,
+                }).collect(),
+                None => None,
+            };
+            let points = match points{
+                Some(data) if data.len() >= 2 => data,
+                _ => return Err(Error::schema("Table must be an array of at least two [raw, value] pairs").within("calibration")),
+            };
+            if points.windows(2).any(|x| x[0].0 >= x[1].0){
+                return Err(Error::schema("Raw values of the table must be strictly increasing").within("calibration"))
+            }
+            let mut inverse:Vec<(f64,f64)> = points.iter().map(|(raw,value)| (*value,*raw)).collect();
+            if inverse[0].0 > inverse[inverse.len()-1].0{
+                inverse.reverse();
+            }
+            let inverse = if inverse.windows(2).all(|x| x[0].0 < x[1].0){ Some(inverse) } else { None };
+            return Ok(Calibration::Table { points, inverse })
+        }
+        if definition.contains_key("scale") || definition.contains_key("offset"){
+            let scale = match definition.get("scale"){
+                Some(data) => data.as_f64(),
+                None => Some(1.),
+            };
+            let offset = match definition.get("offset"){
+                Some(data) => data.as_f64(),
+                None => Some(0.),
+            };
+            return match (offset,scale){
+                (Some(offset),Some(scale)) => Ok(Calibration::Polynomial(vec![offset,scale])),
+                _ => Err(Error::schema("Scale and offset must be numbers").within("calibration")),
+            }
+        }
+        Err(Error::schema("Calibration must contain scale/offset, coefficients or a table").within("calibration"))
+    }
+
+    ///Converts a raw reading to its engineering value
+    pub(crate) fn apply(&self,raw:f64)->f64{
+        match self{
+            Calibration::Polynomial(coefficients) => coefficients.iter().rev().fold(0.,|total,x| total*raw+x),
+            Calibration::Table { points, .. } => interpolate(points,raw),
+        }
+    }
+
+    ///Finds the raw reading that produces the engineering value, searching within the range the raw integer can hold
+    pub(crate) fn invert(&self,value:f64,minimum:f64,maximum:f64)->Result<f64,&'static str>{
+        match self{
+            Calibration::Polynomial(coefficients) => {
+                let a0 = coefficients[0]-value;
+                let a1 = coefficients.get(1).copied().unwrap_or(0.);
+                let a2 = coefficients.get(2).copied().unwrap_or(0.);
+                if coefficients.iter().skip(3).any(|x| *x != 0.){
+                    return Err("Polynomial calibrations above second order can't be inverted")
+                }
+                if a2 == 0.{
+                    if a1 == 0.{
+                        return Err("Constant calibration can't be inverted")
+                    }
+                    return Ok(-a0/a1)
+                }
+                let discriminant = a1*a1-4.*a2*a0;
+                if discriminant < 0.{
+                    return Err("Value is outside of the calibration curve")
+                }
+                let roots = [(-a1+discriminant.sqrt())/(2.*a2),(-a1-discriminant.sqrt())/(2.*a2)];
+                match roots.iter().find(|x| **x >= minimum-0.5 && **x <= maximum+0.5){
+                    Some(root) => Ok(*root),
+                    None => Ok(roots[0]),
+                }
+            },
+            Calibration::Table { inverse, .. } => match inverse{
+                Some(points) => Ok(interpolate(points,value)),
+                None => Err("Calibration table values aren't monotonic, so it can't be inverted"),
+            },
+        }
+    }
+}
+
+///Linear interpolation between points with increasing x, extrapolating from the first or last segment outside the table
+fn interpolate(points:&[(f64,f64)],x:f64)->f64{
+    let segment = match points.windows(2).position(|y| x <= y[1].0){
+        Some(index) => index,
+        None => points.len()-2,
+    };
+    let (x0,y0) = points[segment];
+    let (x1,y1) = points[segment+1];
+    y0+(x-x0)*(y1-y0)/(x1-x0)
+}
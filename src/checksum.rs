@@ -0,0 +1,97 @@
+//! Integrity checks computed over the bytes of a frame, sent as checksum parameters
+//!
+//! A checksum covers a range of bytes of the frame, by default from the start of the frame up to the checksum itself, and
+//! always before the checksum. When encoding, the checksum is filled in once the whole frame has been written, so that
+//! values filled in later (such as lengthOf parameters) are covered with their final value. When decoding, the bytes
+//! before the checksum are already final, so it is verified as soon as it is read.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::Error;
+
+///Algorithm of a checksum parameter
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Checksum{
+    ///CRC-16-CCITT as used by CCSDS (polynomial 0x1021, initial value 0xFFFF, not reflected)
+    Crc16Ccitt,
+    ///CRC-32 as used by Ethernet and zip (polynomial 0x04C11DB7, reflected, initial value and final XOR 0xFFFFFFFF)
+    Crc32,
+    ///Fletcher-16, the second sum in the high byte and the first sum in the low byte
+    Fletcher16,
+    ///XOR of every byte
+    Xor,
+}
+
+impl Checksum{
+    ///Compiles the algorithm keyword
+    pub(crate) fn new(definition:Option<&Value>)->Result<Checksum,Error>{
+        match definition.map(|x| x.as_str()){
+            Some(Some("crc16-ccitt")) => Ok(Checksum::Crc16Ccitt),
+            Some(Some("crc32")) => Ok(Checksum::Crc32),
+            Some(Some("fletcher16")) => Ok(Checksum::Fletcher16),
+            Some(Some("xor")) => Ok(Checksum::Xor),
+            _ => Err(Error::schema("Algorithm field must be one of crc16-ccitt, crc32, fletcher16 or xor")),
+        }
+    }
+
+    ///Size of the checksum in bits
+    pub(crate) fn size(self)->u32{
+        match self{
+            Checksum::Crc16Ccitt | Checksum::Fletcher16 => 16,
+            Checksum::Crc32 => 32,
+            Checksum::Xor => 8,
+        }
+    }
+
+    pub(crate) fn compute(self,data:&[u8])->u64{
+        match self{
+            Checksum::Crc16Ccitt => data.iter().fold(0xFFFF_u16,|crc,x| {
+                (0..8).fold(crc ^ (*x as u16) << 8,|crc,_| if crc & 0x8000 != 0{ (crc << 1) ^ 0x1021 } else { crc << 1 })
+            }) as u64,
+            Checksum::Crc32 => !data.iter().fold(0xFFFF_FFFF_u32,|crc,x| {
+                (0..8).fold(crc ^ *x as u32,|crc,_| if crc & 1 != 0{ (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 })
+            }) as u64,
+            Checksum::Fletcher16 => {
+                let (first,second) = data.iter().fold((0_u32,0_u32),|(first,second),x| {
+                    let first = (first+*x as u32) % 255;
+                    (first,(second+first) % 255)
+                });
+                (second << 8 | first) as u64
+            },
+            Checksum::Xor => data.iter().fold(0,|total,x| total ^ x) as u64,
+        }
+    }
+}
+
+///Bytes covered by a checksum at the given byte offset, from the start offset up to the end offset or else the checksum.
+///None if the range doesn't end before the checksum
+pub(crate) fn covered(start:usize,end:Option<usize>,offset:usize)->Option<Range<usize>>{
+    let end = end.unwrap_or(offset);
+    if start > end || end > offset{
+        return None
+    }
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    #[test]
+    fn test_check_values(){
+        assert_eq!(Checksum::Crc16Ccitt.compute(b"123456789"),0x29B1);
+        assert_eq!(Checksum::Crc32.compute(b"123456789"),0xCBF4_3926);
+        assert_eq!(Checksum::Fletcher16.compute(b"abcde"),0xC8F0);
+        assert_eq!(Checksum::Xor.compute(&[0x12,0x34,0x56]),0x70);
+        assert_eq!(Checksum::Crc16Ccitt.compute(&[]),0xFFFF);
+        assert_eq!(Checksum::Crc32.compute(&[]),0);
+    }
+    #[test]
+    fn test_covered(){
+        assert_eq!(covered(0,None,4),Some(0..4));
+        assert_eq!(covered(1,Some(3),4),Some(1..3));
+        assert_eq!(covered(5,None,4),None);
+        assert_eq!(covered(0,Some(5),4),None);
+    }
+}
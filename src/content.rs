@@ -0,0 +1,131 @@
+//! Representation of binary data within JSON messages
+//!
+//! JSON has no binary type, so blob parameters are given either as a text encoding of the bytes (hex or base64) or as an
+//! array of byte values. The form used when decoding is set per parameter by the contentEncoding keyword, while encoding
+//! accepts both the configured text encoding and a byte array.
+
+use serde_json::Value;
+
+use crate::Error;
+
+const BASE64_ALPHABET:&[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+///How the bytes of a blob parameter appear in the message
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ContentEncoding{
+    ///Hexadecimal string, two characters per byte
+    Base16,
+    ///Standard base64 string with padding
+    Base64,
+    ///Array of integers from 0 to 255
+    Array,
+}
+
+impl ContentEncoding{
+    ///Compiles the contentEncoding keyword, defaulting to hex
+    pub(crate) fn new(definition:Option<&Value>)->Result<ContentEncoding,Error>{
+        match definition.map(|x| x.as_str()){
+            None | Some(Some("base16")) => Ok(ContentEncoding::Base16),
+            Some(Some("base64")) => Ok(ContentEncoding::Base64),
+            Some(Some("array")) => Ok(ContentEncoding::Array),
+            _ => Err(Error::schema("ContentEncoding field must be one of base16, base64 or array")),
+        }
+    }
+
+    ///Represents the bytes in the message
+    pub(crate) fn write(self,data:&[u8])->Value{
+        match self{
+            ContentEncoding::Base16 => Value::String(data.iter().map(|x| format!("{:02x}",x)).collect()),
+            ContentEncoding::Base64 => Value::String(base64_encode(data)),
+            ContentEncoding::Array => data.iter().map(|x| Value::from(*x)).collect(),
+        }
+    }
+
+    ///Reads the bytes from the message, either in this encoding or as a byte array
+    pub(crate) fn read(self,value:&Value)->Result<Vec<u8>,&'static str>{
+        match value{
+            Value::Array(data) => data.iter().map(|x| match x.as_u64().and_then(|y| u8::try_from(y).ok()){
+                Some(byte) => Ok(byte),
+                None => Err("Byte arrays must only contain integers from 0 to 255"),
+            }).collect(),
+            Value::String(data) => match self{
+                ContentEncoding::Base16 => base16_decode(data),
+                ContentEncoding::Base64 => base64_decode(data),
+                ContentEncoding::Array => Err("Blob must be given as an array of bytes"),
+            },
+            _ => Err("Blob must be given as an encoded string or an array of bytes"),
+        }
+    }
+}
+
+fn base16_decode(data:&str)->Result<Vec<u8>,&'static str>{
+    if !data.len().is_multiple_of(2){
+        return Err("Hex string must have an even number of characters")
+    }
+    data.as_bytes().chunks(2).map(|x| {
+        //from_str_radix also accepts a leading sign, so the digits are checked first
+        match std::str::from_utf8(x).ok().filter(|y| y.chars().all(|z| z.is_ascii_hexdigit())){
+            Some(digits) => u8::from_str_radix(digits,16).map_err(|_| "Hex string contains an invalid character"),
+            None => Err("Hex string contains an invalid character"),
+        }
+    }).collect()
+}
+
+fn base64_encode(data:&[u8])->String{
+    let mut output = String::with_capacity(data.len().div_ceil(3)*4);
+    for chunk in data.chunks(3){
+        let bits = chunk.iter().enumerate().fold(0_u32,|total,(index,x)| total | (*x as u32) << (16-8*index));
+        for index in 0..4{
+            if index <= chunk.len(){
+                output.push(BASE64_ALPHABET[(bits >> (18-6*index)) as usize & 0x3F] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+///Decodes standard base64, with or without padding
+fn base64_decode(data:&str)->Result<Vec<u8>,&'static str>{
+    let data = data.trim_end_matches('=');
+    if data.len() % 4 == 1{
+        return Err("Base64 string has an invalid length")
+    }
+    let mut output = Vec::with_capacity(data.len()*3/4);
+    for chunk in data.as_bytes().chunks(4){
+        let mut bits = 0_u32;
+        for (index,x) in chunk.iter().enumerate(){
+            match BASE64_ALPHABET.iter().position(|y| y == x){
+                Some(value) => bits |= (value as u32) << (18-6*index),
+                None => return Err("Base64 string contains an invalid character"),
+            }
+        }
+        for index in 0..chunk.len()-1{
+            output.push((bits >> (16-8*index)) as u8);
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    #[test]
+    fn test_base64_round_trip(){
+        for (data,text) in [(&b""[..],""),(b"f","Zg=="),(b"fo","Zm8="),(b"foo","Zm9v"),(b"foob","Zm9vYg=="),(&[0xFF,0x00,0xFE],"/wD+")]{
+            assert_eq!(base64_encode(data),text);
+            assert_eq!(base64_decode(text),Ok(data.to_vec()));
+        }
+        assert_eq!(base64_decode("Zm9vYg"),Ok(b"foob".to_vec()));
+        assert!(base64_decode("Zm9vY").is_err());
+        assert!(base64_decode("Zm9v!g==").is_err());
+    }
+    #[test]
+    fn test_base16_decode(){
+        assert_eq!(base16_decode("00ffA5"),Ok(vec![0x00,0xFF,0xA5]));
+        assert!(base16_decode("0ff").is_err());
+        assert!(base16_decode("zz").is_err());
+        assert!(base16_decode("+f").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Error types returned by the parser
+//!
+//! Every error carries the path to the failing element, made up of the layer identifiers and parameter names separated by
+//! `/` (for example `EPS_response/OBC_powerstate/Radio_on`), so that the broken field of a packet can be found directly.
+
+use std::fmt;
+
+use serde_json::Value;
+
+///Shared by encoding and decoding, as the same schema and frame layout is wrong in either case
+pub(crate) const CHECKSUM_RANGE_MSG:&str = "Checksum range must end before the checksum";
+
+///Error Enumeration for library errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error{
+    ///Error when loading a schema file
+    SchemaError{
+        ///Description of Error
+        error_msg:String,
+        ///Path to the layer or parameter that is incorrectly defined
+        path:String,
+    },
+    ///Error when a message doesn't fit the schema it is encoded with
+    EncodeError{
+        ///Description of Error
+        error_msg:String,
+        ///Path to the layer or parameter that couldn't be encoded
+        path:String,
+    },
+    ///Error when a received frame doesn't match the schema
+    DecodeError{
+        ///Reason the frame couldn't be decoded
+        kind:DecodeErrorKind,
+        ///Path to the layer or parameter that couldn't be decoded
+        path:String,
+        ///Byte offset in the frame of the start of the failing element
+        offset:usize,
+    },
+}
+
+///Reasons a frame can fail to decode
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeErrorKind{
+    ///The frame ended before all parameters were read
+    Truncated,
+    ///The signal byte doesn't match any subschema of the layer
+    UnknownSignal(u8),
+    ///The encoded enum code isn't one of the options
+    EnumOutOfRange(u64),
+    ///Bits are set in a flags field that don't belong to any flag, given as the mask of those bits
+    UnknownFlags(u64),
+    ///A varint is longer than 64 bits or holds a value outside of the range of its parameter
+    InvalidVarint,
+    ///A day segmented time code holds more milliseconds than a day or more microseconds than a millisecond
+    InvalidTime,
+    ///A constant parameter doesn't hold its value, given as the value that was received
+    ConstMismatch(Value),
+    ///A string field doesn't contain valid UTF-8
+    InvalidUtf8,
+    ///A number field decoded to NaN or infinity, which can't be represented in JSON
+    InvalidNumber,
+    ///Bytes were left over after the final parameter, given as the number of bytes
+    TrailingBytes(usize),
+    ///A parameter took up fewer bytes than given by its lengthOf parameter, given as the number of unused bytes
+    LengthMismatch(usize),
+    ///The range of bytes of a checksum parameter doesn't end before the checksum
+    ChecksumRange,
+    ///A checksum parameter doesn't match the bytes it covers
+    ChecksumMismatch{
+        ///Checksum received in the frame
+        received:u64,
+        ///Checksum computed from the received bytes
+        computed:u64,
+    },
+}
+
+///Non-fatal issue found while encoding or decoding a message, such as a value that lost precision
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning{
+    ///Description of the issue
+    pub warning_msg:String,
+    ///Path to the layer or parameter the warning concerns
+    pub path:String,
+}
+
+impl Warning{
+    pub(crate) fn new(warning_msg:&str)->Warning{
+        Warning { warning_msg: warning_msg.to_string(), path: String::new() }
+    }
+    ///Prepends a parent layer or parameter name to the path of the warning
+    pub(crate) fn within(mut self,segment:&str)->Warning{
+        if self.path.is_empty(){
+            self.path = segment.to_string();
+        } else {
+            self.path.insert(0,'/');
+            self.path.insert_str(0,segment);
+        }
+        self
+    }
+}
+
+impl Error{
+    pub(crate) fn schema(error_msg:&str)->Error{
+        Error::SchemaError { error_msg: error_msg.to_string(), path: String::new() }
+    }
+    pub(crate) fn encode(error_msg:&str)->Error{
+        Error::EncodeError { error_msg: error_msg.to_string(), path: String::new() }
+    }
+    pub(crate) fn decode(kind:DecodeErrorKind,offset:usize)->Error{
+        Error::DecodeError { kind, path: String::new(), offset }
+    }
+    ///Prepends a parent layer or parameter name to the path of the error
+    pub(crate) fn within(mut self,segment:&str)->Error{
+        match &mut self{
+            Error::SchemaError { path, .. } | Error::EncodeError { path, .. } | Error::DecodeError { path, .. } => {
+                if path.is_empty(){
+                    *path = segment.to_string();
+                } else {
+                    path.insert(0,'/');
+                    path.insert_str(0,segment);
+                }
+            },
+        }
+        self
+    }
+    ///Path to the layer or parameter the error occured at, empty if the error concerns the whole message
+    pub fn path(&self)->&str{
+        match self{
+            Error::SchemaError { path, .. } | Error::EncodeError { path, .. } | Error::DecodeError { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Error{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path().is_empty(){ "N/A" } else { self.path() };
+        match self{
+            Error::SchemaError { error_msg, .. } => write!(f,"Error in schema at {}: {}",path,error_msg),
+            Error::EncodeError { error_msg, .. } => write!(f,"Error when encoding message at {}: {}",path,error_msg),
+            Error::DecodeError { kind, offset, .. } => write!(f,"Error when decoding message at {} (byte {}): {}",path,offset,kind),
+        }
+    }
+}
+
+impl std::error::Error for Error{}
+
+impl fmt::Display for Warning{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty(){ "N/A" } else { &self.path };
+        write!(f,"Warning at {}: {}",path,self.warning_msg)
+    }
+}
+
+impl fmt::Display for DecodeErrorKind{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            DecodeErrorKind::Truncated => write!(f,"Frame ended unexpectedly"),
+            DecodeErrorKind::UnknownSignal(signal) => write!(f,"Signal byte {} doesn't match a subschema",signal),
+            DecodeErrorKind::EnumOutOfRange(code) => write!(f,"Enum code {} doesn't match any option",code),
+            DecodeErrorKind::UnknownFlags(mask) => write!(f,"Bits {:#x} don't belong to any flag",mask),
+            DecodeErrorKind::InvalidVarint => write!(f,"Varint is too long for its parameter"),
+            DecodeErrorKind::InvalidTime => write!(f,"Time code is not a valid time"),
+            DecodeErrorKind::ConstMismatch(value) => write!(f,"Constant parameter has the value {}",value),
+            DecodeErrorKind::InvalidUtf8 => write!(f,"String is not valid UTF-8"),
+            DecodeErrorKind::InvalidNumber => write!(f,"Number is NaN or infinite"),
+            DecodeErrorKind::TrailingBytes(count) => write!(f,"{} unexpected bytes after the final parameter",count),
+            DecodeErrorKind::LengthMismatch(count) => write!(f,"{} bytes given by the lengthOf parameter weren't used",count),
+            DecodeErrorKind::ChecksumRange => write!(f,"{}",CHECKSUM_RANGE_MSG),
+            DecodeErrorKind::ChecksumMismatch { received, computed } => write!(f,"Checksum {:#x} doesn't match the computed {:#x}",received,computed),
+        }
+    }
+}
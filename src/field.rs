@@ -0,0 +1,1374 @@
+//! Compiled representation of the parameters within a bottom level packet
+//!
+//! Schemas are compiled into a list of [FieldSpec] once when the [crate::Parser] is created, so that invalid keywords are
+//! caught at load time and encoding/decoding doesn't need to look anything up in the raw JSON.
+
+use serde_json::{Map, Number, Value};
+
+use crate::{calibration::Calibration, checksum::{self, Checksum}, error::CHECKSUM_RANGE_MSG, content::ContentEncoding, time::Time, wire::{Endianness, Reader, Writer}, DecodeErrorKind, Error, Options};
+
+///A single compiled parameter of a packet
+#[derive(Clone, Debug)]
+pub(crate) struct FieldSpec{
+    ///Name of the parameter, used as the key in the message
+    pub(crate) name:String,
+    pub(crate) kind:FieldKind,
+    ///Byte order of the value and any length prefix, if it differs from the frame
+    endianness:Option<Endianness>,
+    ///Value that is always sent, so the parameter is left out of messages
+    constant:Option<Value>,
+    ///Later parameter the value is computed from, so the parameter is left out of messages
+    reference:Option<Reference>,
+    ///Range of values that are accepted when encoding
+    limits:Option<Limits>,
+}
+
+///Range of values of a numeric parameter, compiled from the minimum, maximum, exclusiveMinimum, exclusiveMaximum and
+///multipleOf keywords. Calibrated integers are limited by their engineering value
+#[derive(Clone, Debug, Default)]
+struct Limits{
+    minimum:Option<f64>,
+    maximum:Option<f64>,
+    exclusive_minimum:Option<f64>,
+    exclusive_maximum:Option<f64>,
+    multiple_of:Option<f64>,
+}
+
+impl Limits{
+    fn new(definition:&Map<String,Value>)->Result<Option<Limits>,Error>{
+        let keyword = |name:&str|->Result<Option<f64>,Error>{
+            match definition.get(name){
+                Some(data) => match data.as_f64(){
+                    Some(data2) => Ok(Some(data2)),
+                    None => Err(Error::schema(&format!("{} field must contain a number",name))),
+                },
+                None => Ok(None),
+            }
+        };
+        let limits = Limits {
+            minimum: keyword("minimum")?,
+            maximum: keyword("maximum")?,
+            exclusive_minimum: keyword("exclusiveMinimum")?,
+            exclusive_maximum: keyword("exclusiveMaximum")?,
+            multiple_of: keyword("multipleOf")?,
+        };
+        if limits.multiple_of.is_some_and(|x| x <= 0.){
+            return Err(Error::schema("MultipleOf field must be greater than zero"))
+        }
+        match limits{
+            Limits { minimum: None, maximum: None, exclusive_minimum: None, exclusive_maximum: None, multiple_of: None } => Ok(None),
+            _ => Ok(Some(limits)),
+        }
+    }
+    ///Describes how the value falls outside of the range, if it does
+    fn check(&self,value:f64)->Option<String>{
+        if let Some(minimum) = self.minimum.filter(|x| value < *x){
+            return Some(format!("Value {} is less than the minimum of {}",value,minimum))
+        }
+        if let Some(minimum) = self.exclusive_minimum.filter(|x| value <= *x){
+            return Some(format!("Value {} is not greater than the exclusive minimum of {}",value,minimum))
+        }
+        if let Some(maximum) = self.maximum.filter(|x| value > *x){
+            return Some(format!("Value {} is greater than the maximum of {}",value,maximum))
+        }
+        if let Some(maximum) = self.exclusive_maximum.filter(|x| value >= *x){
+            return Some(format!("Value {} is not less than the exclusive maximum of {}",value,maximum))
+        }
+        if let Some(step) = self.multiple_of{
+            //Decimal steps such as 0.1 aren't exact in binary, so the quotient only has to be close to a whole number
+            let quotient = value/step;
+            if (quotient-quotient.round()).abs() > 1e-9*quotient.abs().max(1.){
+                return Some(format!("Value {} is not a multiple of {}",value,step))
+            }
+        }
+        None
+    }
+}
+
+///How a parameter is computed from a later parameter of the same packet
+#[derive(Clone, Debug)]
+enum Reference{
+    ///Number of elements of the named array, or bytes of the named string or blob
+    Count(String),
+    ///Number of bytes the named parameter takes up
+    Length(String),
+}
+
+impl Reference{
+    fn target(&self)->&str{
+        match self{
+            Reference::Count(target) | Reference::Length(target) => target,
+        }
+    }
+}
+
+///Compiled parameters of a packet or nested object, in the order they are sent
+///
+///Parameters that aren't listed in required are optional. They are sent after the required parameters in order of their
+///name, and a presence bitmap with one bit per optional parameter (padded to a whole number of bytes) is sent in front of
+///everything else.
+///
+///A conditional (if/then/else) selects further parameters depending on the values of the parameters before it, and the
+///selected parameters are sent last.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Fields{
+    order:Vec<FieldSpec>,
+    optional:Vec<FieldSpec>,
+    condition:Option<Box<Conditional>>,
+}
+
+///Parameters sent depending on the values of earlier parameters of the packet
+#[derive(Clone, Debug)]
+struct Conditional{
+    ///Each parameter must have one of the listed values for the condition to hold, with the values as they are decoded
+    when:Vec<(FieldSpec,Vec<Value>)>,
+    then:Fields,
+    otherwise:Fields,
+}
+
+impl Conditional{
+    ///Compiles the if, then and else keywords, with the condition limited to the given earlier parameters
+    fn new(definition:&Map<String,Value>,condition:&Value,earlier:&[&FieldSpec])->Result<Conditional,Error>{
+        let properties = match condition.get("properties").and_then(|x| x.as_object()){
+            Some(data) if !data.is_empty() => data,
+            _ => return Err(Error::schema("If must contain a properties map of the parameters it checks").within("if")),
+        };
+        let mut when = Vec::with_capacity(properties.len());
+        for (name,check) in properties{
+            let field = match earlier.iter().find(|x| x.name == *name){
+                Some(field) => field,
+                None => return Err(Error::schema("Conditions can only check parameters of the packet itself").within(name).within("if")),
+            };
+            //Hidden parameters never appear in messages, so a condition on them could never hold
+            if field.is_hidden(){
+                return Err(Error::schema("Conditions can't check constant, padding, checksum, countOf or lengthOf parameters").within(name).within("if"))
+            }
+            let values = match (check.get("const"),check.get("enum").and_then(|x| x.as_array())){
+                (Some(value),None) => std::slice::from_ref(value),
+                (None,Some(values)) => values.as_slice(),
+                _ => return Err(Error::schema("Conditions must contain either a const or an enum of values").within(name).within("if")),
+            };
+            //Compared as they are decoded, so that for example a condition of 1 holds for a number decoded as 1.0
+            let values = match values.iter().map(|x| field.normalise(x,&Options::default())).collect::<Result<Vec<Value>,Error>>(){
+                Ok(values) => values,
+                Err(_) => return Err(Error::schema("Condition value can't be sent as this parameter").within(name).within("if")),
+            };
+            when.push(((*field).clone(),values));
+        }
+        let branch = |keyword:&str|->Result<Fields,Error>{
+            match definition.get(keyword){
+                Some(Value::Object(data)) => Fields::compile(data,earlier).map_err(|e| e.within(keyword)),
+                Some(_) => Err(Error::schema("Conditional parameters must be a key-value map").within(keyword)),
+                None => Ok(Fields::default()),
+            }
+        };
+        let (then,otherwise) = (branch("then")?,branch("else")?);
+        for name in then.names().into_iter().chain(otherwise.names()){
+            if earlier.iter().any(|x| x.name == name){
+                return Err(Error::schema("Conditional parameter has the same name as a parameter of the packet").within(name))
+            }
+        }
+        Ok(Conditional { when, then, otherwise })
+    }
+    ///Picks the parameters that apply to the values of the packet, along with those of the other branch. Values are
+    ///first passed through their parameter, so that the condition is checked on what is sent whether the message is being
+    ///encoded or was decoded, and whatever the options
+    fn select(&self,values:&Map<String,Value>,options:&Options)->(&Fields,&Fields){
+        let holds = self.when.iter().all(|(field,allowed)| {
+            values.get(&field.name).is_some_and(|x| field.normalise(x,options).is_ok_and(|y| allowed.contains(&y)))
+        });
+        if holds{ (&self.then,&self.otherwise) } else { (&self.otherwise,&self.then) }
+    }
+}
+
+impl Fields{
+    ///Compiles the required and properties keywords of a packet or object definition
+    pub(crate) fn new(definition:&Map<String,Value>)->Result<Fields,Error>{
+        Self::compile(definition,&[])
+    }
+    ///Compiles a packet, object or conditional branch, where conditions may also check the given earlier parameters
+    fn compile(definition:&Map<String,Value>,earlier:&[&FieldSpec])->Result<Fields,Error>{
+        let order = match definition.get("required"){
+            Some(data) => {
+                match data.as_array(){
+                    Some(data2) => data2,
+                    None => return Err(Error::schema("Required Field must be an array")),
+                }
+            },
+            None => return Err(Error::schema("Missing Required Field")),
+        };
+        let scheme = match definition.get("properties"){
+            Some(data) => {
+                match data.as_object(){
+                    Some(data2) => data2,
+                    None => return Err(Error::schema("Properties Field is incorrectly formatted")),
+                }
+            },
+            None => return Err(Error::schema("Missing properties Field")),
+        };
+        let mut fields = Vec::with_capacity(order.len());
+        for i in order{
+            let name = match i.as_str(){
+                Some(data) => data,
+                None => return Err(Error::schema("Required Field must only contain strings")),
+            };
+            match scheme.get(name){
+                Some(definition) => fields.push(FieldSpec::new(name,definition)?),
+                None => return Err(Error::schema("Required parameter is missing from properties").within(name)),
+            }
+        }
+        let optional = scheme.iter()
+            .filter(|(name,_)| !order.iter().any(|x| x == *name))
+            .map(|(name,definition)| FieldSpec::new(name,definition))
+            .collect::<Result<Vec<FieldSpec>,Error>>()?;
+        if let Some(i) = optional.iter().find(|x| x.is_hidden()){
+            return Err(Error::schema("Constant, padding, checksum, countOf and lengthOf parameters must be listed in required").within(&i.name))
+        }
+        //The decoder needs a computed value before it reaches the parameter it describes, so that parameter must come later
+        for index in 0..fields.len(){
+            let reference = match fields[index].reference.clone(){
+                Some(reference) => reference,
+                None => continue,
+            };
+            let target = match fields.iter().skip(index+1).position(|x| x.name == reference.target()){
+                Some(position) => index+1+position,
+                None => return Err(Error::schema("CountOf and lengthOf must name a later required parameter of the packet").within(&fields[index].name)),
+            };
+            if fields[..index].iter().any(|x| x.reference.as_ref().is_some_and(|y| y.target() == reference.target())){
+                return Err(Error::schema("Parameter is referenced by more than one countOf or lengthOf").within(reference.target()))
+            }
+            let explicit = ["length","lengthSize","lengthEncoding","trailing"].iter().any(|x| scheme[reference.target()].get(*x).is_some());
+            fields[target].refer(&reference,explicit).map_err(|e| e.within(reference.target()))?;
+        }
+        let has_condition = definition.contains_key("if");
+        //A trailing value is only delimited by the end of the packet, so nothing can be sent after it
+        for (index,i) in fields.iter().chain(&optional).enumerate(){
+            if i.is_trailing() && (index+1 != fields.len() || !optional.is_empty() || has_condition){
+                return Err(Error::schema("Trailing parameters must be the last parameter of the packet, without optional parameters").within(&i.name))
+            }
+        }
+        let mut output = Fields { order: fields, optional, condition: None };
+        match definition.get("if"){
+            Some(condition) => {
+                let earlier:Vec<&FieldSpec> = earlier.iter().copied().chain(output.order.iter().chain(&output.optional)).collect();
+                let condition = Conditional::new(definition,condition,&earlier)?;
+                output.condition = Some(Box::new(condition));
+            },
+            None if definition.contains_key("then") || definition.contains_key("else") => {
+                return Err(Error::schema("Then and else can only be given with an if"))
+            },
+            None => {},
+        }
+        Ok(output)
+    }
+    ///If the final parameter can take up the rest of the packet
+    fn is_trailing(&self)->bool{
+        match &self.condition{
+            Some(condition) => condition.then.is_trailing() || condition.otherwise.is_trailing(),
+            None => self.order.last().is_some_and(|x| x.is_trailing()),
+        }
+    }
+    ///Names of all parameters that can be sent, including those of conditionals
+    fn names(&self)->Vec<&str>{
+        let mut names:Vec<&str> = self.order.iter().chain(&self.optional).map(|x| x.name.as_str()).collect();
+        if let Some(condition) = &self.condition{
+            names.extend(condition.then.names());
+            names.extend(condition.otherwise.names());
+        }
+        names
+    }
+    ///Size of the presence bitmap in bits
+    fn bitmap_size(&self)->usize{
+        self.optional.len().div_ceil(8)*8
+    }
+    ///Encodes each parameter from the key-value map of the message
+    pub(crate) fn encode(&self,value:&Value,output:&mut Writer,options:&Options)->Result<(),Error>{
+        match value.as_object(){
+            Some(data) => self.encode_map(data,output,options),
+            None => Err(Error::encode("Could not serialize data as a key-value map")),
+        }
+    }
+    fn encode_map(&self,value:&Map<String,Value>,output:&mut Writer,options:&Options)->Result<(),Error>{
+        //Null is treated the same as leaving the parameter out
+        let present:Vec<Option<&Value>> = self.optional.iter().map(|i| value.get(&i.name).filter(|x| !x.is_null())).collect();
+        for data in &present{
+            output.write_bits(data.is_some() as u64,1);
+        }
+        for _ in self.optional.len()..self.bitmap_size(){
+            output.write_bits(0,1);
+        }
+        //LengthOf parameters are sent as zero and filled in once the parameter they measure has been sent
+        let mut lengths:Vec<(&FieldSpec,usize)> = Vec::new();
+        let zero = Value::from(0);
+        for i in &self.order{
+            let computed;
+            let data = match (&i.kind,&i.constant,&i.reference){
+                (FieldKind::Padding { .. } | FieldKind::Checksum { .. },_,_) => &Value::Null,
+                (_,Some(constant),_) => match value.get(&i.name){
+                    //Compared as sent, as the constant is stored as it is decoded (for example 1.0 for a number const of 1)
+                    Some(data) if i.normalise(data,options).ok().as_ref() != Some(constant) => return Err(Error::encode(&format!("Parameter is always {}",constant)).within(&i.name)),
+                    _ => constant,
+                },
+                (_,_,Some(Reference::Count(target))) => {
+                    //An invalid or missing value counts as empty, leaving the error to the parameter itself
+                    let count = self.order.iter().find(|x| x.name == *target).zip(value.get(target)).and_then(|(x,y)| x.count(y));
+                    computed = Value::from(count.unwrap_or(0));
+                    if value.get(&i.name).is_some_and(|x| !x.is_null() && *x != computed){
+                        return Err(Error::encode(&format!("Parameter is the number of elements of {}, which is {}",target,computed)).within(&i.name))
+                    }
+                    &computed
+                },
+                (_,_,Some(Reference::Length(_))) => {
+                    lengths.push((i,output.bit_position()));
+                    &zero
+                },
+                (_,None,None) => match value.get(&i.name){
+                    Some(data) => data,
+                    None => return Err(Error::encode("Message is missing a required parameter").within(&i.name)),
+                },
+            };
+            let start = output.bit_position();
+            i.encode(data,output,options)?;
+            if let Some((length,position)) = lengths.iter().find(|(x,_)| x.reference.as_ref().is_some_and(|y| y.target() == i.name)){
+                length.patch_length(output,*position,output.bit_position()-start,value.get(&length.name))?;
+            }
+        }
+        for (i,data) in self.optional.iter().zip(present){
+            if let Some(data) = data{
+                i.encode(data,output,options)?;
+            }
+        }
+        if let Some(condition) = &self.condition{
+            let (selected,other) = condition.select(value,options);
+            let applies = selected.names();
+            for name in other.names(){
+                if !applies.contains(&name) && value.get(name).is_some_and(|x| !x.is_null()){
+                    return Err(Error::encode("Parameter doesn't apply to the values of the conditions").within(name))
+                }
+            }
+            selected.encode_map(value,output,options)?;
+        }
+        Ok(())
+    }
+    pub(crate) fn decode(&self,message:&mut Reader,options:&Options)->Result<Map<String,Value>,Error>{
+        let mut output = Map::new();
+        self.decode_into(message,options,&mut output)?;
+        Ok(output)
+    }
+    ///Decodes each parameter into the map, which also holds the earlier parameters that conditions can check
+    fn decode_into(&self,message:&mut Reader,options:&Options,output:&mut Map<String,Value>)->Result<(),Error>{
+        let offset = message.position();
+        let mut present = Vec::with_capacity(self.optional.len());
+        for _ in &self.optional{
+            present.push(message.read_bits(1).map_err(|kind| Error::decode(kind,offset))? == 1);
+        }
+        message.read_bits((self.bitmap_size()-self.optional.len()) as u32).map_err(|kind| Error::decode(kind,offset))?;
+        //Values of countOf and lengthOf parameters, until the parameter they describe is reached
+        let mut references:Vec<(&Reference,usize)> = Vec::new();
+        for i in &self.order{
+            let offset = message.position();
+            let at = |kind| Error::decode(kind,offset).within(&i.name);
+            let bound = match references.iter().position(|(x,_)| x.target() == i.name).map(|x| references.swap_remove(x)){
+                Some((Reference::Count(_),count)) => {
+                    message.set_count(count);
+                    None
+                },
+                Some((Reference::Length(_),length)) => Some(message.limit(length).map_err(at)?),
+                None => None,
+            };
+            let data = i.decode(message,options)?;
+            if let Some(end) = bound{
+                if message.remaining_bits() != 0{
+                    return Err(at(DecodeErrorKind::LengthMismatch(message.remaining_bits().div_ceil(8))))
+                }
+                message.unlimit(end);
+            }
+            match (&i.kind,&i.constant,&i.reference){
+                (FieldKind::Padding { .. } | FieldKind::Checksum { .. },_,_) => {},
+                (_,_,Some(reference)) => {
+                    //Saturates so that a corrupted value is reported as truncated rather than wrapping
+                    references.push((reference,data.as_u64().map_or(usize::MAX,|x| usize::try_from(x).unwrap_or(usize::MAX))));
+                },
+                (_,Some(constant),_) if data != *constant => {
+                    if !options.lenient_constants{
+                        return Err(Error::decode(DecodeErrorKind::ConstMismatch(data),offset).within(&i.name))
+                    }
+                    let warnings = message.warning_count();
+                    message.warn(&format!("Constant parameter has the value {} instead of {}",data,constant));
+                    message.warnings_within(warnings,&i.name);
+                },
+                (_,Some(_),_) => {},
+                (_,None,None) => {
+                    output.insert(i.name.clone(),data);
+                },
+            }
+        }
+        for (i,present) in self.optional.iter().zip(present){
+            if present{
+                output.insert(i.name.clone(),i.decode(message,options)?);
+            }
+        }
+        if let Some(condition) = &self.condition{
+            condition.select(output,options).0.decode_into(message,options,output)?;
+        }
+        Ok(())
+    }
+}
+
+///How a decimal value is rounded to the nearest step of its factor
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Rounding{
+    Floor,
+    Round,
+    Ceil,
+}
+
+impl Rounding{
+    fn apply(self,value:f64)->f64{
+        //Values such as 3.3*100 come out as 329.99999999999994, which should still be treated as exactly 330
+        let nearest = value.round();
+        if (value-nearest).abs() <= 1e-9*nearest.abs().max(1.){
+            return nearest
+        }
+        match self{
+            Rounding::Floor => value.floor(),
+            Rounding::Round => nearest,
+            Rounding::Ceil => value.ceil(),
+        }
+    }
+}
+
+///Type specific encoding information of a parameter
+#[derive(Clone, Debug)]
+pub(crate) enum FieldKind{
+    ///Enumerated options with the code sent for each, as an unsigned integer of the given size in bits
+    Enum{options:Vec<(Value,u64)>,size:u32},
+    ///Named bits of an unsigned integer of the given size, ordered by bit number
+    Flags{flags:Vec<(String,u32)>,size:u32},
+    ///Boolean with a size in bits, so that consecutive booleans can be packed into a single byte
+    Boolean{size:u32},
+    ///Integer with a size in bits, either two's complement signed or unsigned, optionally calibrated to engineering units.
+    ///Varint integers are sent as LEB128 (zigzag for signed integers), with the size limiting the range of values
+    Integer{size:u32,signed:bool,calibration:Option<Calibration>,varint:bool},
+    ///IEEE 754 floating point number with a size of 16, 32 or 64 bits
+    Number{size:u32},
+    ///Fixed point value, sent as an integer of the given size holding value*10^factor
+    Decimal{size:u32,factor:i32,signed:bool,rounding:Rounding},
+    ///UTF-8 text
+    String(ByteLength),
+    ///Binary data, represented in the message as given by the content encoding
+    Blob{encoding:ContentEncoding,length:ByteLength},
+    ///CCSDS time code
+    Time(Time),
+    ///Repeated elements of a single parameter definition
+    Array{items:Box<FieldSpec>,length:Length},
+    ///Nested parameters, sent inline as part of the parent
+    Object(Fields),
+    ///Reserved bits, sent as zeros and ignored when decoding
+    Padding{size:u64},
+    ///Integrity check over the bytes of the frame from the start offset up to the end offset, or else up to the checksum
+    Checksum{checksum:Checksum,start:usize,end:Option<usize>},
+}
+
+///How the number of elements of a variable sized parameter is determined
+#[derive(Clone, Debug)]
+pub(crate) enum Length{
+    ///Always the given number of elements, so nothing is sent
+    Fixed(usize),
+    ///The number of elements is sent in front of the elements, as an unsigned integer of the given size in bits
+    Prefixed{size:u32},
+    ///The number of elements is sent in front of the elements as an unsigned LEB128 varint
+    Varint,
+    ///Nothing is sent and the elements take up the rest of the packet, only used for the final string or blob
+    Remaining,
+    ///Nothing is sent as the number of elements is given by an earlier countOf parameter
+    Counted,
+    ///Nothing is sent and the elements take up the rest of the bytes given by an earlier lengthOf parameter
+    Bounded,
+}
+
+impl Length{
+    ///Compiles the length and lengthSize keywords, defaulting to an 8 bit prefix
+    fn new(definition:&Map<String,Value>)->Result<Length,Error>{
+        match definition.get("lengthEncoding"){
+            Some(data) if data.as_str() == Some("varint") => {
+                if definition.contains_key("length") || definition.contains_key("lengthSize"){
+                    return Err(Error::schema("A varint length can't have a length or lengthSize"))
+                }
+                return Ok(Length::Varint)
+            },
+            Some(data) if data.as_str() == Some("fixed") => {},
+            Some(_) => return Err(Error::schema("LengthEncoding field must be either fixed or varint")),
+            None => {},
+        }
+        match (definition.get("length"),definition.get("lengthSize")){
+            (Some(_),Some(_)) => Err(Error::schema("Only one of length and lengthSize can be given")),
+            (Some(length),None) => match length.as_u64().and_then(|x| usize::try_from(x).ok()){
+                Some(length) => Ok(Length::Fixed(length)),
+                None => Err(Error::schema("Length field must contain a positive integer")),
+            },
+            (None,Some(size)) => match size.as_u64(){
+                Some(size) if (1..=32).contains(&size) => Ok(Length::Prefixed { size: size as u32 }),
+                _ => Err(Error::schema("LengthSize field must be a number of bits between 1 and 32")),
+            },
+            (None,None) => Ok(Length::Prefixed { size: 8 }),
+        }
+    }
+    ///Size in bits of the length prefix, if there is one
+    fn size(&self)->Option<u32>{
+        match self{
+            Length::Prefixed { size } => Some(*size),
+            _ => None,
+        }
+    }
+    ///Checks the number of elements fits, writing the prefix if there is one
+    fn encode(&self,count:usize,output:&mut Writer,endianness:Option<Endianness>)->Result<(),Error>{
+        match self{
+            Length::Fixed(length) => {
+                if count != *length{
+                    return Err(Error::encode(&format!("Expected exactly {} elements, got {}",length,count)))
+                }
+            },
+            Length::Prefixed { size } => {
+                if count as u128 >= 1_u128 << size{
+                    return Err(Error::encode(&format!("More than the maximum of {} elements",(1_u64 << size)-1)))
+                }
+                output.write_value(count as u64,*size,endianness);
+            },
+            Length::Varint => output.write_varint(count as u64),
+            Length::Remaining | Length::Counted | Length::Bounded => {},
+        }
+        Ok(())
+    }
+    fn decode(&self,message:&mut Reader,endianness:Option<Endianness>)->Result<usize,DecodeErrorKind>{
+        match self{
+            Length::Fixed(length) => Ok(*length),
+            Length::Prefixed { .. } | Length::Varint | Length::Counted => {
+                let count = match self{
+                    Length::Prefixed { size } => message.read_value(*size,endianness)? as usize,
+                    Length::Varint => message.read_varint()? as usize,
+                    _ => message.take_count().unwrap_or(0),
+                };
+                //Every element takes at least a bit, so a corrupted count can't make the decoder loop for long
+                if count > message.remaining_bits(){
+                    return Err(DecodeErrorKind::Truncated)
+                }
+                Ok(count)
+            },
+            Length::Remaining | Length::Bounded => Ok(message.remaining_bits()/8),
+        }
+    }
+}
+
+///How the bytes of a string or blob parameter are sized
+#[derive(Clone, Debug)]
+pub(crate) struct ByteLength{
+    length:Length,
+    ///Longest value in bytes accepted when encoding
+    max_length:Option<usize>,
+    ///Byte used to pad values shorter than a fixed length
+    fill:u8,
+}
+
+impl ByteLength{
+    ///Compiles the length, lengthSize, trailing, maxLength and fill keywords
+    fn new(definition:&Map<String,Value>)->Result<ByteLength,Error>{
+        let length = match definition.get("trailing"){
+            Some(Value::Bool(true)) => {
+                if ["length","lengthSize","lengthEncoding"].iter().any(|x| definition.contains_key(*x)){
+                    return Err(Error::schema("A trailing value can't have a length, lengthSize or lengthEncoding"))
+                }
+                Length::Remaining
+            },
+            Some(Value::Bool(false)) | None => Length::new(definition)?,
+            Some(_) => return Err(Error::schema("Trailing field must be a boolean")),
+        };
+        let max_length = match definition.get("maxLength"){
+            Some(data) => match data.as_u64().and_then(|x| usize::try_from(x).ok()){
+                Some(data2) => Some(data2),
+                None => return Err(Error::schema("MaxLength field must contain a positive integer")),
+            },
+            None => None,
+        };
+        let fill = match (definition.get("fill"),&length){
+            (Some(data),Length::Fixed(_)) => match data.as_u64().and_then(|x| u8::try_from(x).ok()){
+                Some(data2) => data2,
+                None => return Err(Error::schema("Fill field must be a byte value between 0 and 255")),
+            },
+            (Some(_),_) => return Err(Error::schema("Fill can only be given with a fixed length")),
+            (None,_) => 0,
+        };
+        Ok(ByteLength { length, max_length, fill })
+    }
+    ///Writes the value, preceded by its length or padded to the fixed length
+    fn encode(&self,data:&[u8],output:&mut Writer,endianness:Option<Endianness>)->Result<(),Error>{
+        if let Some(max_length) = self.max_length{
+            if data.len() > max_length{
+                return Err(Error::encode(&format!("Provided value is longer than the maximum of {} bytes",max_length)))
+            }
+        }
+        match self.length{
+            Length::Fixed(length) => {
+                let data = &data[..data.len().min(length)];
+                output.write_bytes(data);
+                output.write_bytes(&vec![self.fill;length-data.len()]);
+            },
+            _ => {
+                self.length.encode(data.len(),output,endianness)?;
+                output.write_bytes(data);
+            },
+        }
+        Ok(())
+    }
+    ///Reads the bytes of the value, including any fill bytes
+    fn decode(&self,message:&mut Reader,endianness:Option<Endianness>)->Result<Vec<u8>,DecodeErrorKind>{
+        let length = self.length.decode(message,endianness)?;
+        message.read_bytes(length)
+    }
+    ///Number of bytes always sent, if the value has a fixed length instead of a length prefix
+    fn fixed(&self)->Option<usize>{
+        match self.length{
+            Length::Fixed(length) => Some(length),
+            _ => None,
+        }
+    }
+}
+
+impl FieldSpec{
+    ///Compiles a single parameter definition from the properties of a packet
+    pub(crate) fn new(name:&str,definition:&Value)->Result<FieldSpec,Error>{
+        Self::compile(name,definition).map_err(|e| e.within(name))
+    }
+    fn compile(name:&str,definition:&Value)->Result<FieldSpec,Error>{
+        let definition = match definition.as_object(){
+            Some(data) => data,
+            None => return Err(Error::schema("Parameter is not a key-value map")),
+        };
+        let kind = match definition.get("enum"){
+            Some(options) => {
+                let size = match definition.get("size"){
+                    Some(_) => Self::size(definition)?,
+                    None => 8,
+                };
+                let options = match options{
+                    Value::Array(options) => options.iter().cloned().zip(0..).collect(),
+                    Value::Object(options) => Self::codes(options,"Enum codes must be positive integers")?,
+                    _ => return Err(Error::schema("Enum options must be an array or a map of names to codes")),
+                };
+                let (_,maximum) = integer_range(size,false);
+                for (index,(name,code)) in options.iter().enumerate(){
+                    if *code as i128 > maximum{
+                        return Err(Error::schema(&format!("Enum code {} of {} doesn't fit in {} bits",code,name,size)))
+                    }
+                    if options[..index].iter().any(|(_,x)| x == code){
+                        return Err(Error::schema(&format!("Enum code {} is used by more than one option",code)))
+                    }
+                }
+                FieldKind::Enum { options, size }
+            },
+            None => {
+                let field_type = match definition.get("type").and_then(|x| x.as_str()){
+                    Some(data) => data,
+                    None => return Err(Error::schema("Parameter is missing a type keyword")),
+                };
+                match field_type{
+                    "boolean" => {
+                        let size = match definition.get("size"){
+                            Some(_) => Self::size(definition)?,
+                            None => 8,
+                        };
+                        if size > 8{
+                            return Err(Error::schema("Boolean size must be between 1 and 8 bits"))
+                        }
+                        FieldKind::Boolean { size }
+                    },
+                    "integer" => {
+                        let calibration = match definition.get("calibration"){
+                            Some(data) => Some(Calibration::new(data)?),
+                            None => None,
+                        };
+                        let varint = match definition.get("encoding"){
+                            Some(data) => match data.as_str(){
+                                Some("varint") => true,
+                                Some("fixed") => false,
+                                _ => return Err(Error::schema("Encoding field must be either fixed or varint")),
+                            },
+                            None => false,
+                        };
+                        //Varints only take the bytes the value needs, so the size just limits the range and defaults to 64 bits
+                        let size = match definition.get("size"){
+                            None if varint => 64,
+                            _ => Self::size(definition)?,
+                        };
+                        FieldKind::Integer { size, signed: Self::signed(definition)?, calibration, varint }
+                    },
+                    "flags" => {
+                        let size = match definition.get("size"){
+                            Some(_) => Self::size(definition)?,
+                            None => 8,
+                        };
+                        let flags = match definition.get("flags"){
+                            Some(Value::Array(flags)) => flags.iter().cloned().zip(0..).collect(),
+                            Some(Value::Object(flags)) => Self::codes(flags,"Flag bits must be positive integers")?,
+                            Some(_) => return Err(Error::schema("Flags field must be an array or a map of names to bit numbers")),
+                            None => return Err(Error::schema("Flags parameters must have a flags field")),
+                        };
+                        let mut names = Vec::with_capacity(flags.len());
+                        let mut mask:u64 = 0;
+                        for (name,bit) in flags{
+                            let name = match name{
+                                Value::String(name) => name,
+                                _ => return Err(Error::schema("Flag names must be strings")),
+                            };
+                            if bit >= size as u64{
+                                return Err(Error::schema(&format!("Flag bit {} of {} doesn't fit in {} bits",bit,name,size)))
+                            }
+                            if mask & (1 << bit) != 0{
+                                return Err(Error::schema(&format!("Flag bit {} is used by more than one flag",bit)))
+                            }
+                            mask |= 1 << bit;
+                            names.push((name,bit as u32));
+                        }
+                        names.sort_by_key(|(_,bit)| *bit);
+                        FieldKind::Flags { flags: names, size }
+                    },
+                    "number" => match definition.get("size"){
+                        Some(_) => match Self::size(definition)?{
+                            size @ (16 | 32 | 64) => FieldKind::Number { size },
+                            _ => return Err(Error::schema("Number size must be 16, 32 or 64 bits")),
+                        },
+                        None => FieldKind::Number { size: 64 },
+                    },
+                    "decimal" => {
+                        let factor = match definition.get("factor"){
+                            //Any 64 bit value times 10^288 is still a finite float, so every value that can be sent can be decoded
+                            Some(data) => match data.as_i64().and_then(|x| i32::try_from(x).ok()){
+                                Some(data2) if data2.abs() <= 288 => data2,
+                                _ => return Err(Error::schema("Factor field must contain an integer between -288 and 288")),
+                            },
+                            None => return Err(Error::schema("Decimal parameters must have a factor field")),
+                        };
+                        let rounding = match definition.get("rounding"){
+                            Some(data) => match data.as_str(){
+                                Some("floor") => Rounding::Floor,
+                                Some("round") => Rounding::Round,
+                                Some("ceil") => Rounding::Ceil,
+                                _ => return Err(Error::schema("Rounding field must be one of floor, round or ceil")),
+                            },
+                            None => Rounding::Floor,
+                        };
+                        FieldKind::Decimal { size: Self::size(definition)?, factor, signed: Self::signed(definition)?, rounding }
+                    },
+                    "string" => FieldKind::String(ByteLength::new(definition)?),
+                    "blob" => FieldKind::Blob {
+                        encoding: ContentEncoding::new(definition.get("contentEncoding"))?,
+                        length: ByteLength::new(definition)?,
+                    },
+                    "time" => FieldKind::Time(Time::new(definition)?),
+                    "padding" => match definition.get("size").and_then(|x| x.as_u64()){
+                        Some(size) if size > 0 => FieldKind::Padding { size },
+                        _ => return Err(Error::schema("Padding parameters must have a size field with a positive number of bits")),
+                    },
+                    "checksum" => {
+                        if definition.contains_key("const"){
+                            return Err(Error::schema("Checksum parameters can't have a const"))
+                        }
+                        let offset = |keyword:&str|->Result<Option<usize>,Error>{
+                            match definition.get(keyword){
+                                Some(data) => match data.as_u64().and_then(|x| usize::try_from(x).ok()){
+                                    Some(data2) => Ok(Some(data2)),
+                                    None => Err(Error::schema(&format!("{} field must contain a positive integer",keyword))),
+                                },
+                                None => Ok(None),
+                            }
+                        };
+                        let (start,end) = (offset("start")?.unwrap_or(0),offset("end")?);
+                        if end.is_some_and(|x| x < start){
+                            return Err(Error::schema("End field must not be before the start field"))
+                        }
+                        FieldKind::Checksum { checksum: Checksum::new(definition.get("algorithm"))?, start, end }
+                    },
+                    "array" => {
+                        let items = match definition.get("items"){
+                            Some(data) => FieldSpec::new("items",data)?,
+                            None => return Err(Error::schema("Array parameters must have an items field")),
+                        };
+                        if items.is_trailing(){
+                            return Err(Error::schema("Array items can't be trailing").within("items"))
+                        }
+                        if items.is_hidden(){
+                            return Err(Error::schema("Array items can't be constant, padding, checksum, countOf or lengthOf").within("items"))
+                        }
+                        FieldKind::Array { items: Box::new(items), length: Length::new(definition)? }
+                    },
+                    "object" => FieldKind::Object(Fields::new(definition)?),
+                    _ => return Err(Error::schema(&format!("Invalid type keyword: {}",field_type))),
+                }
+            },
+        };
+        let endianness = Endianness::new(definition.get("endianness"))?;
+        if endianness.is_some(){
+            let size = match &kind{
+                FieldKind::Integer { varint: true, .. } | FieldKind::Time(_) | FieldKind::Object(_) | FieldKind::Padding { .. } => None,
+                FieldKind::Enum { size, .. } | FieldKind::Flags { size, .. } | FieldKind::Boolean { size } |
+                FieldKind::Integer { size, .. } | FieldKind::Decimal { size, .. } => Some(*size),
+                FieldKind::Number { size } => Some(*size),
+                FieldKind::Checksum { checksum, .. } => Some(checksum.size()),
+                FieldKind::String(ByteLength { length, .. }) | FieldKind::Blob { length: ByteLength { length, .. }, .. } |
+                FieldKind::Array { length, .. } => length.size(),
+            };
+            if size.is_some_and(|x| !x.is_multiple_of(8)){
+                return Err(Error::schema("Endianness can only be set for sizes that are a whole number of bytes"))
+            }
+        }
+        let reference = match (definition.get("countOf"),definition.get("lengthOf")){
+            (None,None) => None,
+            (Some(_),Some(_)) => return Err(Error::schema("Only one of countOf and lengthOf can be given")),
+            (Some(target),None) | (None,Some(target)) => {
+                let target = match target.as_str(){
+                    Some(data) => data.to_string(),
+                    None => return Err(Error::schema("CountOf and lengthOf must contain the name of a parameter")),
+                };
+                if definition.contains_key("const"){
+                    return Err(Error::schema("CountOf and lengthOf can't be given with a const"))
+                }
+                match &kind{
+                    FieldKind::Integer { varint: true, .. } if definition.contains_key("lengthOf") => {
+                        return Err(Error::schema("LengthOf can't be a varint, as its size must be known before the parameter it measures is sent"))
+                    },
+                    FieldKind::Integer { signed: false, calibration: None, .. } => {},
+                    _ => return Err(Error::schema("CountOf and lengthOf can only be given for unsigned integers without a calibration")),
+                }
+                match definition.contains_key("countOf"){
+                    true => Some(Reference::Count(target)),
+                    false => Some(Reference::Length(target)),
+                }
+            },
+        };
+        let limits = Limits::new(definition)?;
+        if limits.is_some() && !matches!(kind,FieldKind::Integer { .. } | FieldKind::Number { .. } | FieldKind::Decimal { .. }){
+            return Err(Error::schema("Minimum, maximum and multipleOf can only be given for integer, number and decimal parameters"))
+        }
+        let mut output = FieldSpec { name: name.to_string(), kind, endianness, constant: None, reference, limits };
+        if let Some(constant) = definition.get("const"){
+            //The raw_values option changes which value is in the message, so a calibrated constant couldn't be compared
+            if matches!(output.kind,FieldKind::Integer { calibration: Some(_), .. }){
+                return Err(Error::schema("Const can't be given for a calibrated integer"))
+            }
+            //Stored as decoded so that decoded values can be compared with it directly
+            match output.normalise(constant,&Options::default()){
+                Ok(constant) => output.constant = Some(constant),
+                Err(_) => return Err(Error::schema("Const value can't be sent as this parameter")),
+            }
+        }
+        Ok(output)
+    }
+    ///Sends the value with the given options and reads it back with the default options, giving the value as it would be
+    ///decoded by default (for example 1 becomes 1.0 for a number, and a raw value becomes the calibrated value)
+    fn normalise(&self,value:&Value,options:&Options)->Result<Value,Error>{
+        let mut writer = Writer::new(Endianness::Little);
+        self.encode_value(value,&mut writer,options)?;
+        let data = writer.into_parts().0;
+        Ok(self.decode_value(&mut Reader::new(&data,Endianness::Little),&Options::default()).unwrap_or_else(|_| value.clone()))
+    }
+    ///If the parameter is sent without appearing in messages, as a constant, padding, checksum or a computed countOf or
+    ///lengthOf
+    fn is_hidden(&self)->bool{
+        self.constant.is_some() || self.reference.is_some() || matches!(self.kind,FieldKind::Padding { .. } | FieldKind::Checksum { .. })
+    }
+    ///Takes the number of elements from a countOf parameter, or the rest of the bytes given by a lengthOf parameter, in
+    ///place of the default length prefix
+    fn refer(&mut self,reference:&Reference,explicit:bool)->Result<(),Error>{
+        if matches!(reference,Reference::Count(_)) && self.is_hidden(){
+            return Err(Error::schema("CountOf can't name a constant parameter"))
+        }
+        let length = match &mut self.kind{
+            FieldKind::Array { length, .. } | FieldKind::String(ByteLength { length, .. }) |
+            FieldKind::Blob { length: ByteLength { length, .. }, .. } => length,
+            _ => match reference{
+                Reference::Count(_) => return Err(Error::schema("CountOf can only name an array, string or blob")),
+                Reference::Length(_) => return Ok(()),
+            },
+        };
+        match (reference,explicit){
+            (Reference::Count(_),true) => Err(Error::schema("A parameter named by countOf can't have a length, lengthSize, lengthEncoding or trailing")),
+            (Reference::Count(_),false) => {
+                *length = Length::Counted;
+                Ok(())
+            },
+            //A parameter with its own length is still bounded, so its length must agree with the lengthOf parameter
+            (Reference::Length(_),true) => Ok(()),
+            (Reference::Length(_),false) => {
+                *length = Length::Bounded;
+                Ok(())
+            },
+        }
+    }
+    ///Fills in a lengthOf parameter that was sent as zero at the bit position, once the parameter it measures has been
+    ///sent in the given number of bits
+    fn patch_length(&self,output:&mut Writer,position:usize,bits:usize,given:Option<&Value>)->Result<(),Error>{
+        let size = match self.kind{
+            FieldKind::Integer { size, .. } => size,
+            _ => return Ok(()),
+        };
+        if !bits.is_multiple_of(8){
+            return Err(Error::encode("Parameter measured by lengthOf must take up a whole number of bytes").within(&self.name))
+        }
+        let length = bits/8;
+        if length as i128 > integer_range(size,false).1{
+            return Err(Error::encode(&format!("Length of {} bytes doesn't fit in {} bits",length,size)).within(&self.name))
+        }
+        if given.is_some_and(|x| !x.is_null() && x.as_u64() != Some(length as u64)){
+            return Err(Error::encode(&format!("Parameter is the length in bytes of the parameter it measures, which is {}",length)).within(&self.name))
+        }
+        output.patch_value(position,length as u64,size,self.endianness);
+        Ok(())
+    }
+    ///Number of elements of the value for a countOf parameter, if it is valid for this parameter
+    fn count(&self,value:&Value)->Option<usize>{
+        match &self.kind{
+            FieldKind::Array { .. } => value.as_array().map(|x| x.len()),
+            FieldKind::String(_) => value.as_str().map(|x| x.len()),
+            FieldKind::Blob { encoding, .. } => encoding.read(value).ok().map(|x| x.len()),
+            _ => None,
+        }
+    }
+    ///If the parameter takes up the rest of the packet
+    fn is_trailing(&self)->bool{
+        match &self.kind{
+            FieldKind::String(length) | FieldKind::Blob { length, .. } => matches!(length.length,Length::Remaining),
+            FieldKind::Object(fields) => fields.is_trailing(),
+            _ => false,
+        }
+    }
+    ///Reads the size keyword in bits, which must be between 1 and 64 bits
+    fn size(definition:&Map<String,Value>)->Result<u32,Error>{
+        let size = match definition.get("size"){
+            Some(data) => match data.as_u64(){
+                Some(data2) => data2,
+                None => return Err(Error::schema("Size field must contain a number")),
+            },
+            None => return Err(Error::schema("Parameter must have a size field")),
+        };
+        if size == 0 || size > 64{
+            return Err(Error::schema("Size must be between 1 and 64 bits"))
+        }
+        Ok(size as u32)
+    }
+    ///Reads a map of names to the integer code of each, as used by enum and flags parameters
+    fn codes(definition:&Map<String,Value>,error_msg:&str)->Result<Vec<(Value,u64)>,Error>{
+        let mut codes = Vec::with_capacity(definition.len());
+        for (name,code) in definition{
+            match code.as_u64(){
+                Some(code) => codes.push((Value::String(name.clone()),code)),
+                None => return Err(Error::schema(error_msg).within(name)),
+            }
+        }
+        Ok(codes)
+    }
+    ///Reads the signed keyword, integers are unsigned unless specified
+    fn signed(definition:&Map<String,Value>)->Result<bool,Error>{
+        match definition.get("signed"){
+            Some(data) => match data.as_bool(){
+                Some(data2) => Ok(data2),
+                None => Err(Error::schema("Signed field must be a boolean")),
+            },
+            None => Ok(false),
+        }
+    }
+
+    ///Encodes a value according to this parameter, appending the bits to the output
+    pub(crate) fn encode(&self,value:&Value,output:&mut Writer,options:&Options)->Result<(),Error>{
+        let warnings = output.warning_count();
+        self.encode_value(value,output,options).map_err(|e| e.within(&self.name))?;
+        output.warnings_within(warnings,&self.name);
+        Ok(())
+    }
+    fn encode_value(&self,value:&Value,output:&mut Writer,options:&Options)->Result<(),Error>{
+        if let Some(error_msg) = self.out_of_limits(value,options){
+            return Err(Error::encode(&error_msg))
+        }
+        match &self.kind{
+            FieldKind::Enum { options: enum_options, size } => {
+                let data = match enum_options.iter().find(|(x,_)| x == value){
+                    Some((_,code)) => *code,
+                    //Raw codes are accepted back so that messages decoded with unknown codes can be re-encoded, but only codes
+                    //that don't belong to an option, as those are the only codes decoded as raw
+                    None => match value.as_u64(){
+                        Some(code) if options.raw_unknown_enums && code as i128 <= integer_range(*size,false).1 &&
+                            !enum_options.iter().any(|(_,x)| *x == code) => code,
+                        _ => return Err(Error::encode("Provided value is not one of the enum options")),
+                    },
+                };
+                output.write_value(data,*size,self.endianness);
+            },
+            FieldKind::Flags { flags, size } => {
+                let mut data = 0;
+                let bit = |name:&str| match flags.iter().find(|(x,_)| x == name){
+                    Some((_,bit)) => Ok(*bit),
+                    None => Err(Error::encode(&format!("{} is not one of the flags",name))),
+                };
+                match value{
+                    Value::Array(set) => for name in set{
+                        match name.as_str(){
+                            Some(name) => data |= 1 << bit(name)?,
+                            None => return Err(Error::encode("Set flags must be given as strings")),
+                        }
+                    },
+                    Value::Object(set) => for (name,state) in set{
+                        match state.as_bool(){
+                            Some(state) => data |= (state as u64) << bit(name)?,
+                            None => return Err(Error::encode("Flag states must be booleans").within(name)),
+                        }
+                    },
+                    _ => return Err(Error::encode("Flags must be an array of set flags or a map of flags to booleans")),
+                }
+                output.write_value(data,*size,self.endianness);
+            },
+            FieldKind::Boolean { size } => {
+                match value.as_bool(){
+                    Some(data) => output.write_bits(data as u64,*size),
+                    None => return Err(Error::encode("Did not provide a valid boolean")),
+                }
+            },
+            FieldKind::Integer { size, signed, calibration, varint } => {
+                let (minimum,maximum) = integer_range(*size,*signed);
+                let current_data = match calibration{
+                    Some(calibration) if !options.raw_values => {
+                        let engineering = match value.as_f64(){
+                            Some(data) => data,
+                            None => return Err(Error::encode("Provided value cannot be deserialized as a number")),
+                        };
+                        match calibration.invert(engineering,minimum as f64,maximum as f64){
+                            Ok(raw) if raw.is_finite() => raw.round() as i128,
+                            Ok(_) => return Err(Error::encode("Calibration can't produce the provided value")),
+                            Err(error_msg) => return Err(Error::encode(error_msg)),
+                        }
+                    },
+                    _ => match value.as_i64(){
+                        Some(data) => data as i128,
+                        None => match value.as_u64(){
+                            Some(data) => data as i128,
+                            None => return Err(Error::encode("Provided value cannot be deserialized as an integer")),
+                        },
+                    },
+                };
+                if current_data < minimum{
+                    return Err(Error::encode(&format!("Provided value is smaller than minimum of {}",minimum)))
+                }
+                if current_data > maximum{
+                    return Err(Error::encode(&format!("Provided value is bigger than maximum of {}",maximum)))
+                }
+                match (varint,signed){
+                    (true,true) => output.write_varint(zigzag(current_data as i64)),
+                    (true,false) => output.write_varint(current_data as u64),
+                    //Two's complement, so the low bits of a negative value are also correct for the smaller width
+                    (false,_) => output.write_value(current_data as u64,*size,self.endianness),
+                }
+            },
+            FieldKind::Number { size } => {
+                let current_data = match value.as_f64(){
+                    Some(x) => x,
+                    None => return Err(Error::encode("Data could not be serialized as a float")),
+                };
+                let (data,sent) = match size{
+                    16 => {
+                        let half = half_from_f64(current_data);
+                        (half as u64,half_to_f64(half))
+                    },
+                    32 => ((current_data as f32).to_bits() as u64,current_data as f32 as f64),
+                    _ => (current_data.to_bits(),current_data),
+                };
+                if sent.is_infinite(){
+                    return Err(Error::encode(&format!("Provided value is too large for a {} bit number",size)))
+                }
+                if sent != current_data{
+                    output.warn(&format!("Provided value {} can't be represented exactly by a {} bit number, sent as {}",current_data,size,sent));
+                }
+                output.write_value(data,*size,self.endianness);
+            },
+            FieldKind::Decimal { size, factor, signed, rounding } => {
+                let current_data = match value.as_f64(){
+                    Some(x) => x,
+                    None => return Err(Error::encode("Data could not be serialized as a float")),
+                };
+                //Compared as integers, as the 64 bit limits round up to the next power of two as floats. The conversion saturates,
+                //so values far beyond i128 are still out of range
+                let processed_data = rounding.apply(scale(current_data,*factor)) as i128;
+                let (minimum,maximum) = integer_range(*size,*signed);
+                if processed_data < minimum{
+                    return Err(Error::encode(&format!("Provided value is smaller than minimum of {}",descale(minimum,*factor))))
+                }
+                if processed_data > maximum{
+                    return Err(Error::encode(&format!("Provided value is bigger than maximum of {}",descale(maximum,*factor))))
+                }
+                output.write_value(processed_data as u64,*size,self.endianness);
+            },
+            FieldKind::String(length) => {
+                let mut carry = match value.as_str(){
+                    Some(data) => data,
+                    None => return Err(Error::encode("Could not serialize data as a string")),
+                };
+                //Truncate on a character boundary so that the receiver still gets valid UTF-8
+                if let Some(mut end) = length.fixed().filter(|x| *x < carry.len()){
+                    while !carry.is_char_boundary(end){
+                        end -= 1;
+                    }
+                    carry = &carry[..end];
+                }
+                length.encode(carry.as_bytes(),output,self.endianness)?;
+            },
+            FieldKind::Blob { encoding, length } => {
+                let carry = match encoding.read(value){
+                    Ok(data) => data,
+                    Err(error_msg) => return Err(Error::encode(error_msg)),
+                };
+                length.encode(&carry,output,self.endianness)?;
+            },
+            //CCSDS time codes are big endian unless the parameter says otherwise
+            FieldKind::Time(time) => time.encode(value,output,self.endianness.or(Some(Endianness::Big)))?,
+            FieldKind::Array { items, length } => {
+                let elements = match value.as_array(){
+                    Some(data) => data,
+                    None => return Err(Error::encode("Could not serialize data as an array")),
+                };
+                length.encode(elements.len(),output,self.endianness)?;
+                for (index,element) in elements.iter().enumerate(){
+                    let warnings = output.warning_count();
+                    items.encode_value(element,output,options).map_err(|e| e.within(&index.to_string()))?;
+                    output.warnings_within(warnings,&index.to_string());
+                }
+            },
+            FieldKind::Object(fields) => fields.encode(value,output,options)?,
+            FieldKind::Padding { size } => {
+                for chunk in (0..*size).step_by(64){
+                    output.write_bits(0,(size-chunk).min(64) as u32);
+                }
+            },
+            FieldKind::Checksum { checksum, start, end } => {
+                let covered = match checksum::covered(*start,*end,output.bit_position()/8){
+                    Some(covered) => covered,
+                    None => return Err(Error::encode(CHECKSUM_RANGE_MSG)),
+                };
+                if let Err(error_msg) = output.write_checksum(*checksum,covered,self.endianness){
+                    return Err(Error::encode(error_msg))
+                }
+            },
+        }
+        Ok(())
+    }
+    ///Decodes this parameter from the front of the message
+    pub(crate) fn decode(&self,message:&mut Reader,options:&Options)->Result<Value,Error>{
+        let warnings = message.warning_count();
+        let output = self.decode_value(message,options).map_err(|e| e.within(&self.name))?;
+        message.warnings_within(warnings,&self.name);
+        Ok(output)
+    }
+    fn decode_value(&self,message:&mut Reader,options:&Options)->Result<Value,Error>{
+        let offset = message.position();
+        let at = move |kind| Error::decode(kind,offset);
+        let output = match &self.kind{
+            FieldKind::Enum { options: enum_options, size } => {
+                let data = message.read_value(*size,self.endianness).map_err(at)?;
+                match enum_options.iter().find(|(_,code)| *code == data){
+                    Some((option,_)) => option.clone(),
+                    None if options.raw_unknown_enums => Value::from(data),
+                    None => return Err(at(DecodeErrorKind::EnumOutOfRange(data))),
+                }
+            },
+            FieldKind::Flags { flags, size } => {
+                let data = message.read_value(*size,self.endianness).map_err(at)?;
+                let unknown = flags.iter().fold(data,|rest,(_,bit)| rest & !(1 << bit));
+                if unknown != 0{
+                    return Err(at(DecodeErrorKind::UnknownFlags(unknown)))
+                }
+                flags.iter().filter(|(_,bit)| data & (1 << bit) != 0).map(|(name,_)| Value::from(name.as_str())).collect()
+            },
+            FieldKind::Boolean { size } => Value::Bool(message.read_bits(*size).map_err(at)? == 1),
+            FieldKind::Number { size } => {
+                let data = message.read_value(*size,self.endianness).map_err(at)?;
+                let number = match size{
+                    16 => half_to_f64(data as u16),
+                    32 => f32::from_bits(data as u32) as f64,
+                    _ => f64::from_bits(data),
+                };
+                Self::float(number).map_err(at)?
+            },
+            FieldKind::Integer { size, signed, calibration, varint } => {
+                let data = match (varint,signed){
+                    (true,true) => {
+                        let data = unzigzag(message.read_varint().map_err(at)?);
+                        let (minimum,maximum) = integer_range(*size,true);
+                        if (data as i128) < minimum || data as i128 > maximum{
+                            return Err(at(DecodeErrorKind::InvalidVarint))
+                        }
+                        data as u64
+                    },
+                    (true,false) => {
+                        let data = message.read_varint().map_err(at)?;
+                        if data as i128 > integer_range(*size,false).1{
+                            return Err(at(DecodeErrorKind::InvalidVarint))
+                        }
+                        data
+                    },
+                    (false,_) => message.read_value(*size,self.endianness).map_err(at)?,
+                };
+                match calibration{
+                    Some(calibration) if !options.raw_values => {
+                        let raw = if *signed{ sign_extend(data,*size) as f64 } else { data as f64 };
+                        Self::float(calibration.apply(raw)).map_err(at)?
+                    },
+                    _ => if *signed{
+                        Value::from(sign_extend(data,*size))
+                    } else {
+                        Value::from(data)
+                    },
+                }
+            },
+            FieldKind::Decimal { size, factor, signed, .. } => {
+                let data = message.read_value(*size,self.endianness).map_err(at)?;
+                if *signed{
+                    Self::float(descale(sign_extend(data,*size).into(),*factor)).map_err(at)?
+                } else {
+                    Self::float(descale(data.into(),*factor)).map_err(at)?
+                }
+            },
+            FieldKind::String(length) => {
+                let mut data = length.decode(message,self.endianness).map_err(at)?;
+                //The padding of fixed length strings isn't part of the text
+                if length.fixed().is_some(){
+                    while data.last() == Some(&length.fill){
+                        data.pop();
+                    }
+                }
+                match String::from_utf8(data){
+                    Ok(text) => Value::String(text),
+                    Err(_) => return Err(at(DecodeErrorKind::InvalidUtf8)),
+                }
+            },
+            FieldKind::Blob { encoding, length } => encoding.write(&length.decode(message,self.endianness).map_err(at)?),
+            FieldKind::Time(time) => time.decode(message,self.endianness.or(Some(Endianness::Big))).map_err(at)?,
+            FieldKind::Array { items, length } => {
+                //Bounded arrays hold as many elements as fit in the bytes given by their lengthOf parameter
+                let count = match length{
+                    Length::Bounded => None,
+                    _ => Some(length.decode(message,self.endianness).map_err(at)?),
+                };
+                let mut elements = Vec::new();
+                while count.map_or(message.remaining_bits() > 0,|x| elements.len() < x){
+                    let (index,start) = (elements.len(),message.remaining_bits());
+                    let warnings = message.warning_count();
+                    elements.push(items.decode_value(message,options).map_err(|e| e.within(&index.to_string()))?);
+                    message.warnings_within(warnings,&index.to_string());
+                    //Elements that take up no bits would never fill the bound
+                    if count.is_none() && message.remaining_bits() == start{
+                        break
+                    }
+                }
+                Value::Array(elements)
+            },
+            FieldKind::Object(fields) => Value::Object(fields.decode(message,options)?),
+            FieldKind::Padding { size } => {
+                for chunk in (0..*size).step_by(64){
+                    message.read_bits((size-chunk).min(64) as u32).map_err(at)?;
+                }
+                Value::Null
+            },
+            FieldKind::Checksum { checksum, start, end } => {
+                let covered = checksum::covered(*start,*end,offset).ok_or(at(DecodeErrorKind::ChecksumRange))?;
+                let received = message.read_value(checksum.size(),self.endianness).map_err(at)?;
+                let computed = checksum.compute(&message.frame()[covered]);
+                if received != computed{
+                    return Err(at(DecodeErrorKind::ChecksumMismatch { received, computed }))
+                }
+                Value::Null
+            },
+        };
+        if options.check_limits{
+            if let Some(warning_msg) = self.out_of_limits(&output,options){
+                message.warn(&warning_msg);
+            }
+        }
+        Ok(output)
+    }
+    ///Describes how the value in the message falls outside of the limits of the parameter, if it does. Raw values of
+    ///calibrated integers aren't checked, as the limits apply to the engineering value
+    fn out_of_limits(&self,value:&Value,options:&Options)->Option<String>{
+        match (&self.limits,&self.kind){
+            (Some(_),FieldKind::Integer { calibration: Some(_), .. }) if options.raw_values => None,
+            (Some(limits),_) => limits.check(value.as_f64()?),
+            (None,_) => None,
+        }
+    }
+    ///JSON can't represent NaN or infinity, so these are reported rather than silently replaced
+    fn float(value:f64)->Result<Value,DecodeErrorKind>{
+        match Number::from_f64(value){
+            Some(number) => Ok(Value::Number(number)),
+            None => Err(DecodeErrorKind::InvalidNumber),
+        }
+    }
+}
+
+///Smallest and largest value that fits within an integer of the given size in bits
+pub(crate) fn integer_range(size:u32,signed:bool)->(i128,i128){
+    if signed{
+        (-(1_i128 << (size-1)),(1_i128 << (size-1))-1)
+    } else {
+        (0,(1_i128 << size)-1)
+    }
+}
+///Interprets the low `size` bits of the data as a two's complement signed integer
+pub(crate) fn sign_extend(data:u64,size:u32)->i64{
+    let shift = 64-size;
+    ((data << shift) as i64) >> shift
+}
+///Maps signed integers onto unsigned ones so that small negative values also make short varints (0, -1, 1, -2 become 0, 1, 2, 3)
+fn zigzag(value:i64)->u64{
+    ((value << 1) ^ (value >> 63)) as u64
+}
+fn unzigzag(value:u64)->i64{
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+///Converts an engineering value to the integer steps of a decimal with the given factor (value*10^factor)
+fn scale(value:f64,factor:i32)->f64{
+    if factor >= 0{
+        value*10_f64.powi(factor)
+    } else {
+        value/10_f64.powi(-factor)
+    }
+}
+///Converts the integer steps of a decimal back to the engineering value, dividing so that exact decimals are kept exact
+fn descale(value:i128,factor:i32)->f64{
+    if factor >= 0{
+        value as f64/10_f64.powi(factor)
+    } else {
+        value as f64*10_f64.powi(-factor)
+    }
+}
+///Converts to the bits of the nearest IEEE 754 half precision number, rounding ties to even. Values too large for half
+///precision become infinity
+fn half_from_f64(value:f64)->u16{
+    let sign = ((value.to_bits() >> 48) & 0x8000) as u16;
+    let abs = value.abs();
+    if abs.is_nan(){
+        return sign | 0x7E00
+    }
+    //Exponent of the value, or of the smallest normal number for values that become subnormal
+    let exponent = if abs >= 2_f64.powi(-14){ ((abs.to_bits() >> 52) as i32-1023).min(16) } else { -14 };
+    //Number of steps of the 10 bit mantissa, which carries into the exponent when it rounds up to 2048
+    let steps = (abs*2_f64.powi(10-exponent)).round_ties_even() as u32;
+    let bits = (((exponent+14) as u32) << 10)+steps;
+    sign | bits.min(0x7C00) as u16
+}
+///Converts the bits of an IEEE 754 half precision number
+fn half_to_f64(bits:u16)->f64{
+    let sign = if bits & 0x8000 != 0{ -1. } else { 1. };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f64;
+    match exponent{
+        0 => sign*mantissa*2_f64.powi(-24),
+        31 if mantissa == 0. => sign*f64::INFINITY,
+        31 => f64::NAN,
+        _ => sign*(1024.+mantissa)*2_f64.powi(exponent-25),
+    }
+}
@@ -5,13 +5,37 @@
 //! 
 //! The Aim of this project is to provide a satellite communication standard that is modern and easier to write and develop from than XML based systems. More can be read (here)[] 
 
-use core::panic;
-use std::{collections::{HashMap, VecDeque}, fmt, str::from_utf8};
-use serde_json::{self, Map, Number, Value};
+use std::collections::{HashMap, VecDeque};
+use serde_json::{self, Map, Value};
+
+mod calibration;
+mod checksum;
+mod content;
+mod error;
+mod field;
+mod time;
+mod wire;
+pub use error::{DecodeErrorKind, Error, Warning};
+use field::Fields;
+use wire::{Endianness, Reader, Writer};
 
 /// Main interface of the library, created from JSONSchema files
 pub struct Parser{
-    schema:MultiLayerSchema
+    schema:MultiLayerSchema,
+    ///Byte and bit order of the frames
+    endianness:Endianness,
+}
+///Options changing how messages are encoded and decoded
+#[derive(Clone, Debug, Default)]
+pub struct Options{
+    ///Skip calibrations, so calibrated integer parameters are encoded from and decoded to their raw value
+    pub raw_values:bool,
+    ///Decode enum codes that don't match any option to the raw code instead of failing, and accept raw codes when encoding
+    pub raw_unknown_enums:bool,
+    ///Report constant parameters that don't hold their value as warnings instead of failing to decode
+    pub lenient_constants:bool,
+    ///Report decoded values outside of the minimum, maximum and multipleOf of their parameter as warnings
+    pub check_limits:bool,
 }
 ///Schema representation within the parser. . Bottom layers are the actual subschemas to transmit
 #[derive(Clone)]
@@ -24,106 +48,93 @@ pub enum MultiLayerSchema{
         lookup: HashMap<String,u8>,
     },
     ///Final Schema for transmission 
-    Bottom(Map<String,Value>)
-}
-///Error Enumeration for library errors
-#[derive(Debug)]
-pub enum Error{
-    ///Error when parsing a schema file
-    ParseError(String),
-    ///Error when encoding or decoding data
-    EncodeError{
-        ///Description of Error
-        error_msg:String,
-        ///Keyword where error occured
-        error_pos:Option<String>,
-    },
+    Bottom(MessageConfig)
 }
-
-impl fmt::Display for Error{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self{
-            Error::ParseError(reason) => write!(f,"Error when parsing file: {}",reason.to_string()),
-            Error::EncodeError { error_msg, error_pos } => write!(f,"Error when processing message at keyword {}: {}",error_pos.clone().unwrap_or("N/A".to_string()).to_string(),error_msg.to_string()),
-            
-        }
-    }
-}
-fn parse_multilayer_schema(schema:Value)->Result<MultiLayerSchema,Error>{
+fn parse_multilayer_schema(schema:&Value,path:&str)->Result<MultiLayerSchema,Error>{
     //if value has oneOf -> not at bottom level. Parse each element recursively 
-    //if value does not have one Of -> at bottom level, return map
-    let starting_schema: &Map<String, Value>;
-    match schema.as_object(){
-        Some(schema) => starting_schema = schema,
-        None => return Err(Error::ParseError("Provided Schema is not a valid Key-Value Map".to_string())),
-    }
+    //if value does not have one Of -> at bottom level, compile the packet
+    let starting_schema = match schema.as_object(){
+        Some(schema) => schema,
+        None => return Err(Error::schema("Provided Schema is not a valid Key-Value Map")),
+    };
     match starting_schema.get("oneOf"){
         Some(x) => {
             let mut output:HashMap<u8,MultiLayerSchema>=Default::default();
-            let subschemes: &Vec<Value>;
-            match x.as_array(){
-                Some(data) => subschemes = data,
-                None => return Err(Error::ParseError("oneOf is incorrectly declared, unable to parse array".to_string())),
-            }
+            let subschemes = match x.as_array(){
+                Some(data) => data,
+                None => return Err(Error::schema("oneOf is incorrectly declared, unable to parse array")),
+            };
             let mut lookup:HashMap<String,u8>=Default::default();
-            for (counter, i) in (0_u8..).zip(subschemes.iter()){//is this order consistant
-                output.insert(counter,parse_multilayer_schema(i.clone())?);
-                let key: String;
-                match i.get("id"){
-                    Some(key_val) => key = key_val.as_str().expect("Will never panic, loaded as a string").to_string(),
-                    None => return Err(Error::ParseError("Could not find subschema with given key".to_string())),
+            for (index, i) in subschemes.iter().enumerate(){
+                let key = match i.get("id").and_then(|x| x.as_str()){
+                    Some(key_val) => key_val.to_string(),
+                    None => return Err(Error::schema("Could not find subschema with given key")),
+                };
+                //Explicit codes pin the signal byte, otherwise the position in the array is used
+                let code = match i.get("code"){
+                    Some(code) => code.as_u64(),
+                    None => Some(index as u64),
+                };
+                let counter = match code.and_then(|x| u8::try_from(x).ok()){
+                    Some(counter) => counter,
+                    None => return Err(Error::schema("Signal code must be an integer between 0 and 255").within(&key)),
+                };
+                if output.contains_key(&counter){
+                    return Err(Error::schema(&format!("Signal code {} is used by more than one subschema",counter)).within(&key))
+                }
+                if lookup.contains_key(&key){
+                    return Err(Error::schema("Subschema id is used more than once").within(&key))
                 }
+                let sub_path = if path.is_empty(){ key.clone() } else { format!("{}/{}",path,key) };
+                output.insert(counter,parse_multilayer_schema(i,&sub_path).map_err(|e| e.within(&key))?);
                 lookup.insert(key,counter);
             }
             Ok(MultiLayerSchema::Layer { schemes: Box::new(output), lookup })
         },//Recursion
         None => {
-            Ok(MultiLayerSchema::Bottom(starting_schema.clone()))
+            Ok(MultiLayerSchema::Bottom(MessageConfig::new(starting_schema,path)?))
         },//Found the bottom
     }
 }
-fn find_schema_encoding(scheme:&MultiLayerSchema,message:&Value,mut message_bits_carry:Vec<u8>)->Result<(MultiLayerSchema,Value,Vec<u8>),Error>{
+fn find_schema_encoding<'a>(scheme:&'a MultiLayerSchema,message:&'a Value,message_bits_carry:&mut Writer)->Result<(&'a MessageConfig,&'a Value),Error>{
     match scheme{
         MultiLayerSchema::Layer { schemes, lookup } => {
-            if message.as_object().unwrap().keys().count() >1{
-                return Err(Error::ParseError("Message has more than one signal key".to_string()))
-            }
-            let signal:&String;
-            match message.as_object().unwrap().keys().next(){
-                Some(flag) => signal=flag,
-                None => return Err(Error::ParseError("Message doesn't have a signal key".to_string())),
-            }
-            let scheme_id:&u8;
-            match lookup.get(signal){
-                Some(id) => scheme_id=id,
-                None => return Err(Error::EncodeError{error_msg: "Unable to get scheme id".to_string(),error_pos: Some(signal.to_string())}),
+            let message = match message.as_object(){
+                Some(data) => data,
+                None => return Err(Error::encode("Message is not a key-value map")),
+            };
+            if message.len() >1{
+                return Err(Error::encode("Message has more than one signal key"))
             }
+            let (signal,sub_message) = match message.iter().next(){
+                Some(flag) => flag,
+                None => return Err(Error::encode("Message doesn't have a signal key")),
+            };
+            let scheme_id = match lookup.get(signal){
+                Some(id) => id,
+                None => return Err(Error::encode("Unable to get scheme id").within(signal)),
+            };
             match schemes.get(scheme_id){
-                Some(id) => {message_bits_carry.push(*scheme_id);
-                    find_schema_encoding(id, message.get(signal).unwrap(),message_bits_carry)
+                Some(id) => {message_bits_carry.write_bits((*scheme_id).into(),8);
+                    find_schema_encoding(id, sub_message,message_bits_carry).map_err(|e| e.within(signal))
                 },
-                None => return Err(Error::EncodeError{error_msg: "Unable to get scheme from scheme id".to_string(),error_pos: Some(signal.to_string())}),
+                None => Err(Error::encode("Unable to get scheme from scheme id").within(signal)),
             }
-            //Should never panic, since 
         },
-        MultiLayerSchema::Bottom(_) => {
-            Ok((scheme.clone(),message.clone(),message_bits_carry))
+        MultiLayerSchema::Bottom(config) => {
+            Ok((config,message))
         },
     }
 }
-fn find_schema_decoding(scheme:&MultiLayerSchema,message:&mut VecDeque<u8>,mut message_values_carry:VecDeque<String>)->Result<(MultiLayerSchema,VecDeque<u8>,VecDeque<String>),Error>{
+fn find_schema_decoding<'a>(scheme:&'a MultiLayerSchema,message:&mut Reader,message_values_carry:&mut VecDeque<String>)->Result<&'a MessageConfig,Error>{
     match scheme{
         MultiLayerSchema::Layer { schemes, lookup } => {
-            let signal: u8;
-            match message.pop_front(){
-                Some(signal_data) => signal = signal_data,
-                None => return Err(Error::EncodeError { error_msg: "Message is empty".to_string(), error_pos: None }),
-            }
-            let sub_scheme: &MultiLayerSchema;
-            match schemes.get(&signal){
-                Some(data) => sub_scheme = data,
-                None => return Err(Error::EncodeError { error_msg: "Provided Message Bit couldn't be found".to_string(), error_pos: Some(signal.to_string()) }),
-            }
+            let offset = message.position();
+            let signal = message.read_u8().map_err(|kind| Error::decode(kind,offset))?;
+            let sub_scheme = match schemes.get(&signal){
+                Some(data) => data,
+                None => return Err(Error::decode(DecodeErrorKind::UnknownSignal(signal),offset)),
+            };
             for (key,value) in lookup.iter(){
                 if *value == signal{
                     message_values_carry.push_back(key.clone())
@@ -131,90 +142,63 @@ fn find_schema_decoding(scheme:&MultiLayerSchema,message:&mut VecDeque<u8>,mut m
             }
             find_schema_decoding(sub_scheme, message,message_values_carry)
         },
-        MultiLayerSchema::Bottom(_) => {
-            Ok((scheme.clone(),message.clone(),message_values_carry))
+        MultiLayerSchema::Bottom(config) => {
+            Ok(config)
         },
     }
 }
 
-struct MessageConfig{
-    order:Vec<Value>,
-    scheme:Value,
+///Compiled bottom level packet definition, ready for encoding and decoding
+#[derive(Clone)]
+pub struct MessageConfig{
+    id:String,
+    ///Signal keys leading to this packet, used to report errors
+    path:String,
+    order:Fields,
+    raw:Map<String,Value>,
 }
 impl MessageConfig{
-    fn new(schema:MultiLayerSchema)->Result<MessageConfig,Error>{
-        match schema{
-            MultiLayerSchema::Layer {.. } => panic!("Didn't return a bottom level scheme"),//Should never happen (Errors are caught before this point)
-            MultiLayerSchema::Bottom(x) => {
-                Ok(MessageConfig{ order: Self::order(&x)?, scheme: Self::scheme(&x)? })
-            },
-        }
-    }
-    fn order(properties:&Map<String,Value>)->Result<Vec<Value>,Error>{//TODO
-        let id;
-        match properties.get("id"){
+    fn new(properties:&Map<String,Value>,path:&str)->Result<MessageConfig,Error>{
+        let id = match properties.get("id"){
             Some(data) => {
                 match data.as_str(){
-                    Some(data2) => id=data2,
-                    None => return Err(Error::ParseError("One of the ID values is not a string".to_string())),
+                    Some(data2) => data2,
+                    None => return Err(Error::schema("One of the ID values is not a string")),
                 }
             },
-            None => return Err(Error::ParseError("Missing an ID value".to_string())),
-        }
-        let order: &Vec<Value>;
-        match properties.get("required"){
-            Some(data) => {
-                match data.as_array(){
-                    Some(data2) => order=data2,
-                    None => return Err(Error::EncodeError{error_msg:"Required Field must be an array".to_string(),error_pos:Some(id.to_string())}),
-                }
-            },
-            None => return Err(Error::EncodeError{error_msg:"Missing Required Field".to_string(),error_pos:Some(id.to_string())}),
-        }
-        if order.is_empty(){
-            match properties.get("properties"){
-                Some(data) => {
-                    match data.as_object(){
-                        Some(data2) => {
-                            if data2.is_empty(){
-                                return Ok(Vec::new())
-                            } else {
-                                return Err(Error::EncodeError{error_msg:"Required Field is empty!".to_string(),error_pos:Some(id.to_string())})
-                            }
-                        },
-                        None => return Err(Error::EncodeError{error_msg:"Properties Field is incorrectly formatted".to_string(),error_pos:Some(id.to_string())}),
-                    }
-                },
-                None => return Err(Error::EncodeError{error_msg:"Missing properties Field".to_string(),error_pos:Some(id.to_string())}),
-            }
-        }
-
-        Ok(order.clone())
+            None => return Err(Error::schema("Missing an ID value")),
+        };
+        Ok(MessageConfig { id: id.to_string(), path: path.to_string(), order: Fields::new(properties)?, raw: properties.clone() })
     }
-    fn scheme(properties:&Map<String,Value>)->Result<Value,Error>{
-        let id;
-        match properties.get("id"){
-            Some(data) => {
-                match data.as_str(){
-                    Some(data2) => id=data2,
-                    None => return Err(Error::ParseError("One of the ID values is not a string".to_string())),
-                }
-            },
-            None => return Err(Error::ParseError("Missing an ID value".to_string())),
+    ///Identifier of the packet
+    pub fn id(&self)->&str{
+        &self.id
+    }
+    ///The raw JSONschema definition the packet was compiled from
+    pub fn schema(&self)->&Map<String,Value>{
+        &self.raw
+    }
+    fn within(&self,error:Error)->Error{
+        if self.path.is_empty(){
+            error
+        } else {
+            error.within(&self.path)
         }
-        let scheme: Value;
-        match properties.get("properties"){
-            Some(data) => scheme = data.clone(),//Maybe put properties validation here
-            None => return Err(Error::EncodeError{error_msg:"Missing properties Field".to_string(),error_pos:Some(id.to_string())}),
+    }
+    fn warning_within(&self,warning:Warning)->Warning{
+        if self.path.is_empty(){
+            warning
+        } else {
+            warning.within(&self.path)
         }
-        Ok(scheme)
     }
 }
 impl Parser{
     ///Creates a new parser from a serde_json value  
     pub fn new(scheme: Value)->Result<Parser,Error>{
-        let schema = parse_multilayer_schema(scheme)?;
-        Ok(Parser {schema})  
+        let schema = parse_multilayer_schema(&scheme,"")?;
+        let endianness = Endianness::new(scheme.get("endianness"))?.unwrap_or_default();
+        Ok(Parser {schema, endianness})  
     }
 
     ///Creates a new parser from a String schema
@@ -222,7 +206,7 @@ impl Parser{
         let data: Result<serde_json::Value, serde_json::Error> = serde_json::from_str(&scheme);
         match data{
             Ok(value) => Self::new(value),
-            Err(_) => Err(Error::ParseError("Schema could not be serialized into key-value map".to_string())),
+            Err(_) => Err(Error::schema("Schema could not be serialized into key-value map")),
         }
     }
 
@@ -231,143 +215,25 @@ impl Parser{
         let data: Result<serde_json::Value, serde_json::Error> = serde_json::from_str(message);
         match data{
             Ok(value) => self.encode(value),
-            Err(_) => Err(Error::ParseError("String could not be serialized into key-value map".to_string())),
+            Err(_) => Err(Error::encode("String could not be serialized into key-value map")),
         }
     }
     ///Encode a given JSON message into vec[u8]
     pub fn encode(&self,message:Value)->Result<Vec<u8>,Error>{
-        //Can assume this is correctly packed
-        let (message_conf,pre_processed_message,signal_bit) = find_schema_encoding(&self.schema, &message, vec![])?;
-        let message_config = MessageConfig::new(message_conf)?;
-        let mut processed_data =vec![signal_bit];
-        for i in &message_config.order{
-            let unprocessed_data = pre_processed_message.get(i.as_str().unwrap()).unwrap();//Can this fail?
-            let current_config = message_config.scheme.get(i.as_str().unwrap()).unwrap().clone();
-            let mut output:Vec<u8>;
-            match current_config.get("enum"){
-                Some(x) => {
-                    let data:u8;
-                    match x.as_array().unwrap().iter().position(|x| x == unprocessed_data){
-                        Some(data2) => data =data2.try_into().expect("More than 256 enum options"),
-                        None => return Err(Error::EncodeError { error_msg: "Could not get index of provided enum value".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                    };
-                    output = data.to_le_bytes().to_vec();
-                },
-                None => {//not enum
-                    match current_config.get("type").unwrap().as_str().unwrap(){
-                        "boolean" => {
-                            match unprocessed_data.as_bool(){
-                                Some(data) => {
-                                    match data{
-                                        true => output = vec![1],
-                                        false => output = vec![0],
-                                    }
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "Did not provide a valid boolean".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }
-                        },
-                        "integer" => {
-                            let len:u32;
-                            match current_config.get("size"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => match u32::try_from(data2){
-                                        Ok(x) => len = x,
-                                        Err(_) => return Err(Error::EncodeError { error_msg: "Size value is more than 32 bits long".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                    },
-                                    None => return Err(Error::EncodeError { error_msg: "Size field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "Integer parameters must have a size field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            //should I check the schema before now and assume its valid at this point? Might be more trivial to just check it once and keep these errors to the message
-                            let current_data ; 
-                            match unprocessed_data.as_i64(){
-                                Some(data) => current_data = data,
-                                None => return Err(Error::EncodeError { error_msg: "Provided value cannot be deserialized as an integer".to_string(), error_pos: Some(i.as_str().unwrap().to_string())}),
-                            }
-                            if current_data < 0{
-                                if 2_i64.pow(len-1)<current_data{
-                                    return Err(Error::EncodeError { error_msg: "Provided value is bigger than maximum".to_string(), error_pos: Some(i.as_str().unwrap().to_string())})
-                                }
-                            } else if 2_i64.pow(len)<current_data{
-                                return Err(Error::EncodeError { error_msg: "Provided value is bigger than maximum".to_string(), error_pos: Some(i.as_str().unwrap().to_string())})
-                            }
-                                //Then its signed
-                            output = current_data.to_le_bytes().split_at((len/8) as usize).0.to_vec();
-                        },
-                        "string" => {
-                            let mut carry: Vec<u8>;
-                            match unprocessed_data.as_str(){
-                                Some(data) => carry = data.as_bytes().to_vec(),
-                                None => return Err(Error::EncodeError { error_msg: "Could not serialize data as a string".to_string(), error_pos: Some(i.as_str().unwrap().to_string())}),
-                            }
-                            let length = carry.len();
-                            if length > 256{
-                                return Err(Error::EncodeError { error_msg: "Provided string is more than 255 bytes long".to_string(), error_pos: Some(i.as_str().unwrap().to_string())})
-                            }
-                            output = vec![length as u8];
-                            output.append(&mut carry);
-                        },
-                        "number" => {
-                            //64byte signed float
-                            let current_data:f64;
-                            match unprocessed_data.as_f64(){
-                                Some(x) => current_data = x,
-                                None => return Err(Error::EncodeError { error_msg: "Data could not be serialized as a float".to_string(), error_pos: Some(i.as_str().unwrap().to_string())}),
-                            }
-                            output = current_data.to_le_bytes().to_vec();
-                        },
-                        "decimal" => {
-                            let len:u32;
-                            match current_config.get("size"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => match u32::try_from(data2){
-                                        Ok(x) => len = x,
-                                        Err(_) => return Err(Error::EncodeError { error_msg: "Size value is more than 32 bits long".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                    },
-                                    None => return Err(Error::EncodeError { error_msg: "Size field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "decimal parameters must have a size field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            let multiplyer:u64;
-                            match current_config.get("factor"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => multiplyer = data2,
-                                    None => return Err(Error::EncodeError { error_msg: "factor field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "decimal parameters must have a size field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            let current_data:f64;
-                            match unprocessed_data.as_f64(){
-                                Some(x) => current_data = x,
-                                None => return Err(Error::EncodeError { error_msg: "Data could not be serialized as a float".to_string(), error_pos: Some(i.as_str().unwrap().to_string())}),
-                            }
-                            let processed_data = current_data*(10.*multiplyer as f64);
-                            let post_processed_data = processed_data.floor() as u64;
-                            if post_processed_data > len.into(){
-                                return Err(Error::EncodeError { error_msg: "Provided value is larger than spec".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) })
-                            }
-                            output = current_data.to_le_bytes().split_at((len/8) as usize).0.to_vec();
-                        },
-                        "blob" => {
-                            let mut carry: Vec<u8>;
-                            match unprocessed_data.as_str(){
-                                Some(data) => carry = data.as_bytes().to_vec(),
-                                None => return Err(Error::EncodeError { error_msg: "Could not serialize data as a string".to_string(), error_pos: Some(i.as_str().unwrap().to_string())}),
-                            }
-                            let length = carry.len();
-                            if length > 256{
-                                return Err(Error::EncodeError { error_msg: "Provided blob is more than 255 bytes long".to_string(), error_pos: Some(i.as_str().unwrap().to_string())})
-                            }
-                            output = vec![length as u8];
-                            output.append(&mut carry);
-                        },
-                        _ => return Err(Error::EncodeError { error_msg: "Invalid property keyword".to_string(), error_pos: Some(i.as_str().unwrap().to_string())})
-                    }
-                },
-            }
-            processed_data.push(output)
-        }
-    Ok(processed_data.into_iter().flatten().collect())//still need to add pre-append bits
+        self.encode_with_options(message,&Options::default())
+    }
+    ///Encode a given JSON message into vec[u8], with the given [Options]
+    pub fn encode_with_options(&self,message:Value,options:&Options)->Result<Vec<u8>,Error>{
+        Ok(self.encode_with_warnings(message,options)?.0)
+    }
+    ///Encode a given JSON message into vec[u8], with the given [Options], also returning the [Warning]s about values that
+    ///couldn't be sent exactly
+    pub fn encode_with_warnings(&self,message:Value,options:&Options)->Result<(Vec<u8>,Vec<Warning>),Error>{
+        let mut processed_data = Writer::new(self.endianness);
+        let (message_config,pre_processed_message) = find_schema_encoding(&self.schema, &message, &mut processed_data)?;
+        message_config.order.encode(pre_processed_message,&mut processed_data,options).map_err(|e| message_config.within(e))?;
+        let (data,warnings) = processed_data.into_parts();
+        Ok((data,warnings.into_iter().map(|x| message_config.warning_within(x)).collect()))
     }
     ///Decode vec[u8] to a string (Formatted as JSON)
     pub fn decode_to_string(&self,message:Vec<u8>)->Result<String,Error>{
@@ -376,102 +242,25 @@ impl Parser{
     }
     ///Decode vec[u8] to a serde_json::value Object
     pub fn decode(&self,message: Vec<u8>,)->Result<Value,Error>{
-        let mut working_message:VecDeque<u8> = message.into();
-        let mut output = serde_json::Map::new();
-        let (message_conf,mut working_message,mut signal_values) = find_schema_decoding(&self.schema,&mut working_message,vec![].into())?;
-        let message_configs = MessageConfig::new(message_conf)?;
-        for i in message_configs.order{
-            let current_config = message_configs.scheme.get(i.as_str().unwrap()).unwrap().clone();
-            match current_config.get("enum"){
-                Some(x) => {
-                    let data:u8 = working_message.pop_front().unwrap();
-                    output.insert(i.as_str().unwrap().to_string(),x.as_array().unwrap().get(data as usize).unwrap().clone());
-                },
-                None => {
-                    match current_config.get("type").unwrap().as_str().unwrap(){
-                        "boolean" => {
-                            let data:u8 = working_message.pop_front().unwrap();
-                            if data == 1{
-                                output.insert(i.as_str().unwrap().to_string(),Value::Bool(true));
-                            } else {
-                                output.insert(i.as_str().unwrap().to_string(),Value::Bool(false));
-                            }
-                        },
-                        "number" => {
-                            //always f64
-                            let data:Vec<u8> = working_message.drain(0..8).collect();
-                            let working_output:f64 = f64::from_le_bytes(data.as_slice().try_into().expect("Incorrect Length"));
-                            output.insert(i.as_str().unwrap().to_string(),Value::Number(Number::from_f64(working_output).expect("Couldn't convert to JSON")));
-                        },
-                        "integer" => {
-                            let len:u32;
-                            match current_config.get("size"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => match u32::try_from(data2){
-                                        Ok(x) => len = x,
-                                        Err(_) => return Err(Error::EncodeError { error_msg: "Size value is more than 32 bits long".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                    },
-                                    None => return Err(Error::EncodeError { error_msg: "Size field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "Integer parameters must have a size field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            //Again, will this be checked at another point?
-                            let mut data:Vec<u8> = working_message.drain(0..len as usize/8).collect();
-                            data.reverse();//is this needed
-                            while data.len() <8{
-                                data.push(0)
-                            }
-                            let working_output:u64 = u64::from_le_bytes(data.as_slice().try_into().expect("Incorrect Length"));
-                            output.insert(i.as_str().unwrap().to_string(),Value::Number(working_output.into()));
-                        },
-                        "string" => {
-                            let length  = working_message.pop_front().unwrap();
-                            let data:Vec<u8> = working_message.drain(0..length as usize).collect();
-                            let working_output:String = from_utf8(&data).expect("Can't convert to UTF8").to_string();
-                            output.insert(i.as_str().unwrap().to_string(),Value::String(working_output)); 
-                        },
-                        "decimal" => {
-                            let len:u32;
-                            match current_config.get("size"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => match u32::try_from(data2){
-                                        Ok(x) => len = x,
-                                        Err(_) => return Err(Error::EncodeError { error_msg: "Size value is more than 32 bits long".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                    },
-                                    None => return Err(Error::EncodeError { error_msg: "Size field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "Decimal parameters must have a size field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            let multiplyer:u64;
-                            match current_config.get("factor"){
-                                Some(data) => match data.as_u64(){
-                                    Some(data2) => multiplyer = data2,
-                                    None => return Err(Error::EncodeError { error_msg: "factor field must contain a number".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                                },
-                                None => return Err(Error::EncodeError { error_msg: "decimal parameters must have a factor field".to_string(), error_pos: Some(i.as_str().unwrap().to_string()) }),
-                            }//Size in bits
-                            //Again, will this be checked at another point?
-                            let mut data:Vec<u8> = working_message.drain(0..len as usize/8).collect();
-                            data.reverse();//is this needed
-                            while data.len() <8{
-                                data.push(0)
-                            }
-                            let working_output:u64 = u64::from_le_bytes(data.as_slice().try_into().expect("Incorrect Length"));
-                            let post_processed = working_output as f64/(10*multiplyer) as f64;
-                            output.insert(i.as_str().unwrap().to_string(),Value::Number(Number::from_f64(post_processed).expect("Couldn't convert to JSON")));
-                        },
-                        "blob" => {
-                            let length  = working_message.pop_front().unwrap();
-                            let data:Vec<u8> = working_message.drain(0..length as usize).collect();
-                            let working_output:String = from_utf8(&data).expect("Can't convert to UTF8").to_string();
-                            output.insert(i.as_str().unwrap().to_string(),Value::String(working_output)); 
-                        },
-                        _=> panic!("Not implemented for decoding")
-                    }
-                },
-            }
+        self.decode_with_options(message,&Options::default())
+    }
+    ///Decode vec[u8] to a serde_json::value Object, with the given [Options]
+    pub fn decode_with_options(&self,message: Vec<u8>,options:&Options)->Result<Value,Error>{
+        Ok(self.decode_with_warnings(message,options)?.0)
+    }
+    ///Decode vec[u8] to a serde_json::value Object, with the given [Options], also returning the [Warning]s about values
+    ///that were accepted despite not matching the schema
+    pub fn decode_with_warnings(&self,message: Vec<u8>,options:&Options)->Result<(Value,Vec<Warning>),Error>{
+        let mut working_message = Reader::new(&message,self.endianness);
+        let mut signal_values = VecDeque::new();
+        let message_configs = find_schema_decoding(&self.schema,&mut working_message,&mut signal_values)?;
+        let output = message_configs.order.decode(&mut working_message,options).map_err(|e| message_configs.within(e))?;
+        //Less than a byte left over is the padding of the final byte
+        if working_message.remaining_bits() >= 8{
+            return Err(message_configs.within(Error::decode(DecodeErrorKind::TrailingBytes(working_message.remaining_bits()/8),working_message.position())))
         }
-        Ok(Value::from(Self::create_output_package(output,&mut signal_values)))
+        let warnings = working_message.into_warnings().into_iter().map(|x| message_configs.warning_within(x)).collect();
+        Ok((Value::from(Self::create_output_package(output,&mut signal_values)),warnings))
     }    
     fn create_output_package(message:Map<String,Value>,frontmatter:&mut VecDeque<String>)->Map<String, Value>{
         if !frontmatter.is_empty(){
@@ -500,7 +289,7 @@ impl Parser{
                 let top_level:Vec<String> = lookup.keys().cloned().collect();
                 top_level
             },
-            MultiLayerSchema::Bottom(x) => vec![x.get("id").unwrap().as_str().unwrap().to_string()],
+            MultiLayerSchema::Bottom(x) => vec![x.id().to_string()],
         }
     }
 }
@@ -512,10 +301,13 @@ mod tests{
     use std::fs;
 
     use super::*;
+    ///Schema with a single required parameter called field
+    fn single_field_schema(field:Value)->Value{
+        serde_json::json!({"id":"Status","type":"object","required":["field"],"properties":{"field":field}})
+    }
     #[test]
     fn test_loading(){
         Parser::new_from_string(fs::read_to_string(r"src/test_files/scheme.json").expect("Could not read schema file")).unwrap();
-        assert!(true)
     }
     #[test]
     fn test_encoding(){
@@ -616,4 +408,800 @@ mod tests{
         let expected_message:Value = serde_json::from_str(&fs::read_to_string(r"src/test_files/Incoming_data_singleton.json").expect("Could not read incoming data file")).unwrap();
         assert_eq!(decoded_message.as_object().unwrap(),expected_message.as_object().unwrap())
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_loading_rejects_bad_size(){
+        let scheme = r#"{"id":"Bad","type":"object","required":["value"],"properties":{"value":{"type":"integer","size":72}}}"#;
+        assert!(matches!(Parser::new_from_string(scheme.to_string()),Err(Error::SchemaError { .. })))
+    }
+    #[test]
+    fn test_loading_rejects_bad_type(){
+        let scheme = r#"{"id":"Bad","oneOf":[{"id":"Inner","type":"object","required":["value"],"properties":{"value":{"type":"float"}}}]}"#;
+        assert!(matches!(Parser::new_from_string(scheme.to_string()),Err(Error::SchemaError { .. })))
+    }
+    #[test]
+    fn test_loading_rejects_missing_property(){
+        let scheme = r#"{"id":"Bad","type":"object","required":["value"],"properties":{}}"#;
+        assert!(matches!(Parser::new_from_string(scheme.to_string()),Err(Error::SchemaError { .. })))
+    }
+    #[test]
+    fn test_decode_truncated(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/multi_schema_test.json").expect("Could not read schema")).unwrap();
+        let message = [0, 0, 50, 4, 84, 101, 115, 116, 1, 0, 0, 0, 0, 0, 0, 43, 64];
+        for length in 0..message.len(){
+            match parser.decode(message[..length].to_vec()){
+                Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. }) => (),
+                other => panic!("Expected truncation error for length {}, got {:?}",length,other),
+            }
+        }
+    }
+    #[test]
+    fn test_decode_malformed(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/multi_schema_test.json").expect("Could not read schema")).unwrap();
+        assert!(matches!(parser.decode(vec![9]),Err(Error::DecodeError { kind: DecodeErrorKind::UnknownSignal(9), .. })));
+        assert!(matches!(parser.decode(vec![0, 7, 50, 0, 1, 0, 0, 0, 0, 0, 0, 43, 64]),Err(Error::DecodeError { kind: DecodeErrorKind::EnumOutOfRange(7), .. })));
+        assert!(matches!(parser.decode(vec![0, 0, 50, 2, 0xC3, 0x28, 1, 0, 0, 0, 0, 0, 0, 43, 64]),Err(Error::DecodeError { kind: DecodeErrorKind::InvalidUtf8, .. })));
+        assert!(matches!(parser.decode(vec![0, 0, 50, 0, 1, 0, 0, 0, 0, 0, 0, 0xF8, 0x7F]),Err(Error::DecodeError { kind: DecodeErrorKind::InvalidNumber, .. })));
+        assert!(matches!(parser.decode(vec![1, 5, 0, 0]),Err(Error::DecodeError { kind: DecodeErrorKind::TrailingBytes(2), .. })));
+    }
+    #[test]
+    fn test_decode_random_frames(){
+        //Cheap deterministic stand-in for the fuzz target, so that decode panics are caught by cargo test
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/multi_schema_test.json").expect("Could not read schema")).unwrap();
+        let mut state:u64 = 0x2545F4914F6CDD1D;
+        for _ in 0..10000{
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let length = (state % 24) as usize;
+            let message:Vec<u8> = (0..length).map(|x| (state >> (x % 8 * 8)) as u8 ^ x as u8).collect();
+            let _ = parser.decode(message);
+        }
+    }
+    #[test]
+    fn test_error_paths(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/multi_schema_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.decode(vec![2,0,1]).unwrap_err();
+        assert_eq!(error,Error::DecodeError { kind: DecodeErrorKind::Truncated, path: "EPS_response/OBC_powerstate/Radio_on".to_string(), offset: 3 });
+        assert_eq!(error.to_string(),"Error when decoding message at EPS_response/OBC_powerstate/Radio_on (byte 3): Frame ended unexpectedly");
+        let error = parser.encode_from_string(r#"{"EPS_response":{"OBC_powerstate":{"OBC_on":true,"Radio_on":5}}}"#).unwrap_err();
+        assert!(matches!(error,Error::EncodeError { .. }));
+        assert_eq!(error.path(),"EPS_response/OBC_powerstate/Radio_on");
+        let scheme = r#"{"id":"Bad","oneOf":[{"id":"Inner","type":"object","required":["value"],"properties":{"value":{"type":"float"}}}]}"#;
+        assert_eq!(Parser::new_from_string(scheme.to_string()).err().unwrap().path(),"Inner/value");
+    }
+    #[test]
+    fn test_explicit_signal_codes(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/signal_code_test.json").expect("Could not read schema")).unwrap();
+        let encoded_message = parser.encode_from_string(r#"{"Beacon":{"mode":3}}"#).unwrap();
+        assert_eq!(encoded_message,[0x20,3]);
+        let encoded_message = parser.encode_from_string(r#"{"Ping":{}}"#).unwrap();
+        assert_eq!(encoded_message,[1]);
+        let encoded_message = parser.encode_from_string(r#"{"Status":{"Battery":{"level":7}}}"#).unwrap();
+        assert_eq!(encoded_message,[0x10,5,7]);
+        let decoded_message = parser.decode(vec![0x10,5,7]).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"Status":{"Battery":{"level":7}}}));
+        assert!(matches!(parser.decode(vec![0,3]),Err(Error::DecodeError { kind: DecodeErrorKind::UnknownSignal(0), .. })));
+    }
+    #[test]
+    fn test_duplicate_signal_codes(){
+        let scheme = r#"{"id":"Bad","oneOf":[{"id":"A","code":1,"type":"object","required":[],"properties":{}},{"id":"B","type":"object","required":[],"properties":{}}]}"#;
+        let error = Parser::new_from_string(scheme.to_string()).err().unwrap();
+        assert!(matches!(error,Error::SchemaError { .. }));
+        assert_eq!(error.path(),"B");
+        let scheme = r#"{"id":"Bad","oneOf":[{"id":"A","code":256,"type":"object","required":[],"properties":{}}]}"#;
+        assert!(matches!(Parser::new_from_string(scheme.to_string()),Err(Error::SchemaError { .. })));
+    }
+    #[test]
+    fn test_signed_integers(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/integer_test.json").expect("Could not read schema")).unwrap();
+        let message = r#"{"temperature":-40,"offset":-300,"counter":65535,"delta":-9223372036854775808,"uptime":18446744073709551615}"#;
+        let encoded_message = parser.encode_from_string(message).unwrap();
+        let expected_message = [216, 212, 254, 255, 255, 0, 0, 0, 0, 0, 0, 0, 128, 255, 255, 255, 255, 255, 255, 255, 255];
+        assert_eq!(encoded_message,expected_message);
+        let decoded_message = parser.decode(encoded_message).unwrap();
+        assert_eq!(decoded_message,serde_json::from_str::<Value>(message).unwrap());
+    }
+    #[test]
+    fn test_integer_ranges(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/integer_test.json").expect("Could not read schema")).unwrap();
+        let valid = r#"{"temperature":127,"offset":-32768,"counter":0,"delta":9223372036854775807,"uptime":0}"#;
+        assert_eq!(parser.decode(parser.encode_from_string(valid).unwrap()).unwrap(),serde_json::from_str::<Value>(valid).unwrap());
+        for message in [
+            r#"{"temperature":128,"offset":0,"counter":0,"delta":0,"uptime":0}"#,
+            r#"{"temperature":-129,"offset":0,"counter":0,"delta":0,"uptime":0}"#,
+            r#"{"temperature":0,"offset":32768,"counter":0,"delta":0,"uptime":0}"#,
+            r#"{"temperature":0,"offset":0,"counter":65536,"delta":0,"uptime":0}"#,
+            r#"{"temperature":0,"offset":0,"counter":-1,"delta":0,"uptime":0}"#,
+            r#"{"temperature":0,"offset":0,"counter":0,"delta":9223372036854775808,"uptime":0}"#,
+            r#"{"temperature":0,"offset":0,"counter":0,"delta":0,"uptime":-1}"#,
+        ]{
+            assert!(matches!(parser.encode_from_string(message),Err(Error::EncodeError { .. })),"{} should be out of range",message);
+        }
+    }
+    #[test]
+    fn test_bit_packed_fields(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/bit_packed_test.json").expect("Could not read schema")).unwrap();
+        let message = r#"{"mode":5,"adc":2748,"safe":true,"heater":false,"temperature":-3}"#;
+        let encoded_message = parser.encode_from_string(message).unwrap();
+        assert_eq!(encoded_message,[0xE5,0xD5,0xFA]);
+        let decoded_message = parser.decode(encoded_message).unwrap();
+        assert_eq!(decoded_message,serde_json::from_str::<Value>(message).unwrap());
+        assert!(matches!(parser.encode_from_string(r#"{"mode":8,"adc":0,"safe":true,"heater":false,"temperature":0}"#),Err(Error::EncodeError { .. })));
+        assert!(matches!(parser.decode(vec![0xE5,0xD5]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, offset: 2, .. })));
+    }
+    #[test]
+    fn test_decimal_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/decimal_test.json").expect("Could not read schema")).unwrap();
+        let message = r#"{"voltage":3.3,"temperature":-12.3,"altitude":35700.0,"current":0.125,"pointing":0.5}"#;
+        let encoded_message = parser.encode_from_string(message).unwrap();
+        //330, -123 in 12 bits, 357, 125 and 5 packed least significant bit first
+        let mut expected = 330_u64 | ((-123_i64 as u64 & 0xFFF) << 16) | (357 << 28) | (125 << 44);
+        expected |= 5 << 60;
+        let mut expected_message = expected.to_le_bytes().to_vec();
+        expected_message.push(0);
+        assert_eq!(encoded_message,expected_message);
+        let decoded_message = parser.decode(encoded_message).unwrap();
+        assert_eq!(decoded_message,serde_json::from_str::<Value>(message).unwrap());
+    }
+    #[test]
+    fn test_decimal_rounding(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/decimal_test.json").expect("Could not read schema")).unwrap();
+        let message = r#"{"voltage":3.309,"temperature":-12.34,"altitude":35786,"current":0.1236,"pointing":0.41}"#;
+        let decoded_message = parser.decode(parser.encode_from_string(message).unwrap()).unwrap();
+        let expected = r#"{"voltage":3.3,"temperature":-12.4,"altitude":35700.0,"current":0.124,"pointing":0.5}"#;
+        assert_eq!(decoded_message,serde_json::from_str::<Value>(expected).unwrap());
+    }
+    #[test]
+    fn test_decimal_ranges(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/decimal_test.json").expect("Could not read schema")).unwrap();
+        for message in [
+            r#"{"voltage":655.36,"temperature":0,"altitude":0,"current":0,"pointing":0}"#,
+            r#"{"voltage":-0.01,"temperature":0,"altitude":0,"current":0,"pointing":0}"#,
+            r#"{"voltage":0,"temperature":204.8,"altitude":0,"current":0,"pointing":0}"#,
+            r#"{"voltage":0,"temperature":-204.9,"altitude":0,"current":0,"pointing":0}"#,
+        ]{
+            assert!(matches!(parser.encode_from_string(message),Err(Error::EncodeError { .. })),"{} should be out of range",message);
+        }
+        let message = r#"{"voltage":655.35,"temperature":-204.8,"altitude":0.0,"current":0.0,"pointing":0.0}"#;
+        let decoded_message = parser.decode(parser.encode_from_string(message).unwrap()).unwrap();
+        assert_eq!(decoded_message,serde_json::from_str::<Value>(message).unwrap());
+        //The 64 bit limits can't be represented exactly as floats, so values just past them must still be rejected
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"decimal","size":64,"factor":0}))).unwrap();
+        assert!(parser.encode(serde_json::json!({"field":18446744073709551616.0})).is_err());
+        assert!(parser.encode(serde_json::json!({"field":1e300})).is_err());
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"decimal","size":64,"factor":0,"signed":true}))).unwrap();
+        assert!(parser.encode(serde_json::json!({"field":9223372036854775808.0})).is_err());
+        assert_eq!(parser.decode(parser.encode(serde_json::json!({"field":-9223372036854775808.0})).unwrap()).unwrap(),serde_json::json!({"field":-9223372036854775808.0}));
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"decimal","size":64,"factor":-400}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"decimal","size":64,"factor":289}))).is_err());
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"decimal","size":64,"factor":-288}))).unwrap();
+        assert!(parser.decode(vec![0xFF;8]).is_ok());
+    }
+    #[test]
+    fn test_calibration_decode(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/calibration_test.json").expect("Could not read schema")).unwrap();
+        let raw = 700_u64 | (100 << 12) | (64 << 28) | (10 << 36);
+        let message = raw.to_le_bytes()[..6].to_vec();
+        let decoded_message = parser.decode(message.clone()).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"temperature":20.0,"pressure":61.0,"thermistor":-7.5,"humidity":5.01}));
+        let decoded_message = parser.decode_with_options(message,&Options { raw_values: true, ..Default::default() }).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"temperature":700,"pressure":100,"thermistor":64,"humidity":10}));
+    }
+    #[test]
+    fn test_calibration_encode(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/calibration_test.json").expect("Could not read schema")).unwrap();
+        let expected_message = (700_u64 | (100 << 12) | (64 << 28) | (10 << 36)).to_le_bytes()[..6].to_vec();
+        let raw_message = serde_json::json!({"temperature":700,"pressure":100,"thermistor":64,"humidity":10});
+        assert_eq!(parser.encode_with_options(raw_message,&Options { raw_values: true, ..Default::default() }).unwrap(),expected_message);
+        //Third order polynomials can't be inverted
+        let message = serde_json::json!({"temperature":20.0,"pressure":61.0,"thermistor":-7.5,"humidity":5.01});
+        let error = parser.encode(message).unwrap_err();
+        assert!(matches!(error,Error::EncodeError { .. }));
+        assert_eq!(error.path(),"humidity");
+        let mut scheme:Value = serde_json::from_str(&fs::read_to_string(r"src/test_files/calibration_test.json").expect("Could not read schema")).unwrap();
+        scheme["required"] = serde_json::json!(["temperature","pressure","thermistor"]);
+        scheme["properties"].as_object_mut().unwrap().remove("humidity");
+        let calibrated = Parser::new(scheme).unwrap();
+        let message = serde_json::json!({"temperature":20.0,"pressure":61.0,"thermistor":-7.5});
+        assert_eq!(calibrated.encode(message).unwrap(),(700_u64 | (100 << 12) | (64 << 28)).to_le_bytes()[..5].to_vec());
+        assert!(matches!(calibrated.encode(serde_json::json!({"temperature":400.0,"pressure":61.0,"thermistor":-7.5})),Err(Error::EncodeError { .. })));
+    }
+    #[test]
+    fn test_array_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/array_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"panel_currents":[1,2,3,4,5,4095],"file_ids":[258,772],"deployed":[[true,false],[false,true],[true,true]]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        let mut expected_message = vec![0x01,0x20,0x00,0x03,0x40,0x00,0x05,0xF0,0xFF];
+        expected_message.extend([2,0x02,0x01,0x04,0x03]);
+        expected_message.extend([0b1001_0011,0b0011]);
+        assert_eq!(encoded_message,expected_message);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let empty = serde_json::json!({"panel_currents":[0,0,0,0,0,0],"file_ids":[],"deployed":[]});
+        assert_eq!(parser.decode(parser.encode(empty.clone()).unwrap()).unwrap(),empty);
+    }
+    #[test]
+    fn test_array_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/array_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"panel_currents":[1,2,3],"file_ids":[],"deployed":[]})).unwrap_err();
+        assert_eq!(error.path(),"panel_currents");
+        let error = parser.encode(serde_json::json!({"panel_currents":[0,0,0,0,0,0],"file_ids":[1,70000],"deployed":[]})).unwrap_err();
+        assert_eq!(error.path(),"file_ids/1");
+        let deployed = vec![serde_json::json!([true,true]);16];
+        let error = parser.encode(serde_json::json!({"panel_currents":[0,0,0,0,0,0],"file_ids":[],"deployed":deployed})).unwrap_err();
+        assert_eq!(error.path(),"deployed");
+        let mut message = vec![0;9];
+        message.extend([200,1,0]);
+        assert!(matches!(parser.decode(message),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+    }
+    #[test]
+    fn test_object_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/object_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Attitude":{
+            "mode":"Nadir",
+            "attitude":{"q0":0.6,"q1":0.0,"q2":-0.8,"q3":0.0},
+            "wheels":[{"enabled":true,"speed":-1200},{"enabled":false,"speed":0},{"enabled":true,"speed":3000}]
+        }});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        let mut expected_message = vec![0,2,0x70,0x17,0,0,0xC0,0xE0,0,0];
+        expected_message.extend([1,0x50,0xFB,0,0,0,1,0xB8,0x0B]);
+        assert_eq!(encoded_message,expected_message);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_object_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/object_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Attitude":{
+            "mode":"Nadir",
+            "attitude":{"q0":0.6,"q1":0.0,"q2":-0.8},
+            "wheels":[]
+        }});
+        assert_eq!(parser.encode(message).unwrap_err().path(),"Attitude/attitude/q3");
+        let message = serde_json::json!({"Attitude":{
+            "mode":"Nadir",
+            "attitude":{"q0":0.6,"q1":0.0,"q2":-0.8,"q3":0.0},
+            "wheels":[{"enabled":true,"speed":0},{"enabled":false,"speed":0},{"enabled":"yes","speed":0}]
+        }});
+        assert_eq!(parser.encode(message).unwrap_err().path(),"Attitude/wheels/2/enabled");
+        let error = parser.decode(vec![0,2,0x70,0x17,0,0,0xC0,0xE0,0,0,1,0x50,0xFB,0,0,0,1,0xB8]).unwrap_err();
+        assert_eq!(error,Error::DecodeError { kind: DecodeErrorKind::Truncated, path: "Attitude/wheels/2/speed".to_string(), offset: 17 });
+    }
+    #[test]
+    fn test_optional_parameters(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/optional_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Housekeeping":{"battery_voltage":7400,"radio_rssi":-90}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0,0b100,0xE8,0x1C,0xA6]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let with_null = serde_json::json!({"Housekeeping":{"battery_voltage":7400,"camera_temp":null,"radio_rssi":-90}});
+        assert_eq!(parser.encode(with_null).unwrap(),[0,0b100,0xE8,0x1C,0xA6]);
+        let message = serde_json::json!({"Housekeeping":{"battery_voltage":7400,"camera_temp":-5,"gps_fix":true,"radio_rssi":-90}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0,0b111,0xE8,0x1C,0xFB,0x01 | (0xA6 << 1),0xA6 >> 7]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let error = parser.encode(serde_json::json!({"Housekeeping":{"radio_rssi":-90}})).unwrap_err();
+        assert_eq!(error.path(),"Housekeeping/battery_voltage");
+    }
+    #[test]
+    fn test_optional_nested_object(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/optional_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Events":{}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[1,0]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"Events":{"reboot":{"count":3,"reason":"Brownout"}}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[1,1,1,3,2]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        assert!(matches!(parser.decode(vec![1,1,0]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+    }
+    #[test]
+    fn test_enum_codes(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/enum_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"mode":"SAFE","event":"LATCHUP","power":"On"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x10,0x02,0x20,1]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let error = parser.encode(serde_json::json!({"mode":"SAFE","event":"REBOOT","power":"On"})).unwrap_err();
+        assert_eq!(error.path(),"event");
+    }
+    #[test]
+    fn test_enum_unknown_codes(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/enum_test.json").expect("Could not read schema")).unwrap();
+        let message = vec![0x10,0x03,0x20,1];
+        assert_eq!(parser.decode(message.clone()),Err(Error::DecodeError { kind: DecodeErrorKind::EnumOutOfRange(0x2003), path: "event".to_string(), offset: 1 }));
+        let options = Options { raw_unknown_enums: true, ..Default::default() };
+        let decoded_message = parser.decode_with_options(message.clone(),&options).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"mode":"SAFE","event":0x2003,"power":"On"}));
+        assert_eq!(parser.encode_with_options(decoded_message.clone(),&options).unwrap(),message);
+        assert!(parser.encode(decoded_message).is_err());
+        let schema = serde_json::json!({"id":"Mode","type":"object","required":["mode"],"properties":{"mode":{"enum":[10,20]}}});
+        let parser = Parser::new(schema).unwrap();
+        assert_eq!(parser.encode_with_options(serde_json::json!({"mode":20}),&options).unwrap(),[1]);
+        assert_eq!(parser.encode_with_options(serde_json::json!({"mode":2}),&options).unwrap(),[2]);
+        assert!(parser.encode_with_options(serde_json::json!({"mode":1}),&options).is_err());
+    }
+    #[test]
+    fn test_enum_schema_errors(){
+        let schema = |options:Value,size:u64| serde_json::json!({"id":"Mode","type":"object","required":["mode"],"properties":{"mode":{"enum":options,"size":size}}});
+        let error = Parser::new(schema(serde_json::json!({"SAFE":1,"NOMINAL":1}),8)).err().unwrap();
+        assert_eq!(error.path(),"mode");
+        assert!(Parser::new(schema(serde_json::json!({"SAFE":256}),8)).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"SAFE":256}),16)).is_ok());
+        assert!(Parser::new(schema(serde_json::json!({"SAFE":-1}),8)).is_err());
+        let many_options:Vec<Value> = (0..300).map(|x| Value::from(format!("Option{}",x))).collect();
+        assert!(Parser::new(schema(Value::from(many_options.clone()),8)).is_err());
+        let parser = Parser::new(schema(Value::from(many_options),16)).unwrap();
+        let message = serde_json::json!({"mode":"Option299"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x2B,0x01]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_flags_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/flags_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"status":["RADIO_ON","HEATER"],"deployed":["PANEL_X"]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x01,0x28]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"status":{"RADIO_ON":true,"GPS_LOCK":false,"HEATER":true},"deployed":{"PANEL_X":true}});
+        assert_eq!(parser.encode(message).unwrap(),[0x01,0x28]);
+        let message = serde_json::json!({"status":[],"deployed":[]});
+        assert_eq!(parser.decode(parser.encode(message.clone()).unwrap()).unwrap(),message);
+        let schema = serde_json::json!({"id":"Status","type":"object","required":["status"],"properties":{"status":{"type":"flags","size":64,"flags":{"LOW":0,"HIGH":40,"TOP":63}}}});
+        let parser = Parser::new(schema).unwrap();
+        let message = serde_json::json!({"status":["LOW","HIGH","TOP"]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x01,0,0,0,0,0x01,0,0x80]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_flags_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/flags_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"status":["RADIO_ON","SOLAR"],"deployed":[]})).unwrap_err();
+        assert_eq!(error.path(),"status");
+        assert!(parser.encode(serde_json::json!({"status":"RADIO_ON","deployed":[]})).is_err());
+        assert_eq!(parser.decode(vec![0x03,0x28]),Err(Error::DecodeError { kind: DecodeErrorKind::UnknownFlags(0x2), path: "status".to_string(), offset: 0 }));
+        let schema = |flags:Value| serde_json::json!({"id":"Status","type":"object","required":["status"],"properties":{"status":{"type":"flags","flags":flags}}});
+        assert!(Parser::new(schema(serde_json::json!({"A":8}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"A":1,"B":1}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!([1,2]))).is_err());
+    }
+    #[test]
+    fn test_blob_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/blob_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"image":"00ff80","key":"/wD+","chunk":[1,2,255]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[3,0x00,0xFF,0x80,3,0xFF,0x00,0xFE,3,1,2,255]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let array_message = serde_json::json!({"image":[0,255,128],"key":[255,0,254],"chunk":[1,2,255]});
+        assert_eq!(parser.encode(array_message).unwrap(),[3,0x00,0xFF,0x80,3,0xFF,0x00,0xFE,3,1,2,255]);
+    }
+    #[test]
+    fn test_blob_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/blob_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"image":"0g","key":"","chunk":[]})).unwrap_err();
+        assert_eq!(error.path(),"image");
+        let error = parser.encode(serde_json::json!({"image":"","key":"","chunk":[1,256]})).unwrap_err();
+        assert_eq!(error.path(),"chunk");
+        let error = parser.encode(serde_json::json!({"image":"","key":"","chunk":"0102"})).unwrap_err();
+        assert_eq!(error.path(),"chunk");
+        let schema = serde_json::json!({"id":"Blob","type":"object","required":["data"],"properties":{"data":{"type":"blob","contentEncoding":"base32"}}});
+        assert!(Parser::new(schema).is_err());
+    }
+    #[test]
+    fn test_string_lengths(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/string_length_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"callsign":"PA3XYZ","note":"hi","chunk":[1,2]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[b'P',b'A',b'3',b'X',b'Y',b'Z',2,b'h',b'i',2,0,1,2]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"callsign":"PA3","note":"","chunk":[]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[b'P',b'A',b'3',b' ',b' ',b' ',0,0,0]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let encoded_message = parser.encode(serde_json::json!({"callsign":"PA3XYZW","note":"","chunk":[]})).unwrap();
+        assert_eq!(&encoded_message[..6],b"PA3XYZ");
+        let encoded_message = parser.encode(serde_json::json!({"callsign":"PA3XY\u{e9}","note":"","chunk":[]})).unwrap();
+        assert_eq!(parser.decode(encoded_message).unwrap()["callsign"],"PA3XY");
+        let chunk = vec![7_u8;300];
+        let message = serde_json::json!({"callsign":"PA3XYZ","note":"","chunk":chunk});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(&encoded_message[7..9],[0x2C,0x01]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_string_length_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/string_length_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"callsign":"PA3XYZ","note":"hello there","chunk":[]})).unwrap_err();
+        assert_eq!(error.path(),"note");
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"string"}))).unwrap();
+        assert!(parser.encode(serde_json::json!({"field":"a".repeat(256)})).is_err());
+        let encoded_message = parser.encode(serde_json::json!({"field":"a".repeat(255)})).unwrap();
+        assert_eq!(encoded_message[0],255);
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"string","fill":32}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"string","length":4,"fill":256}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"blob","length":4,"lengthSize":8}))).is_err());
+    }
+    #[test]
+    fn test_trailing_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/trailing_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Log":{"level":"Warning","text":"Battery low"}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[&[0_u8,1][..],b"Battery low"].concat());
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"Log":{"level":"Info","text":""}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0,0]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"Dump":{"memory":{"address":0x20000000,"data":"deadbeef"}}});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[1,0,0,0,0x20,0xDE,0xAD,0xBE,0xEF]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_trailing_schema_errors(){
+        let schema = |required:Value,properties:Value| serde_json::json!({"id":"Log","type":"object","required":required,"properties":properties});
+        let text = serde_json::json!({"type":"string","trailing":true});
+        let level = serde_json::json!({"type":"integer","size":8});
+        let error = Parser::new(schema(serde_json::json!(["text","level"]),serde_json::json!({"text":text,"level":level}))).err().unwrap();
+        assert_eq!(error.path(),"text");
+        assert!(Parser::new(schema(serde_json::json!(["text"]),serde_json::json!({"text":text,"level":level}))).is_err());
+        let lines = serde_json::json!({"type":"array","items":text});
+        assert!(Parser::new(schema(serde_json::json!(["lines"]),serde_json::json!({"lines":lines}))).is_err());
+        let text = serde_json::json!({"type":"string","trailing":true,"lengthSize":16});
+        assert!(Parser::new(schema(serde_json::json!(["text"]),serde_json::json!({"text":text}))).is_err());
+    }
+    #[test]
+    fn test_big_endian_schema(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/endianness_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"version":0,"type":true,"apid":0x123,"counter":0x0102,"temperature":0x0304,"voltage":3.3,"name":"ab"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x11,0x23,0x01,0x02,0x04,0x03,0x01,0x4A,0x00,0x02,b'a',b'b']);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_big_endian_fields(){
+        let schema = serde_json::json!({"id":"Status","type":"object","required":["uptime","samples"],"properties":{
+            "uptime":{"type":"integer","size":32,"endianness":"big"},
+            "samples":{"type":"array","lengthSize":16,"endianness":"big","items":{"type":"number","endianness":"big"}},
+        }});
+        let parser = Parser::new(schema).unwrap();
+        let message = serde_json::json!({"uptime":0x01020304,"samples":[1.0]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[&[0x01,0x02,0x03,0x04,0x00,0x01][..],&1.0_f64.to_be_bytes()].concat());
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"integer","size":12,"endianness":"big"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"integer","size":16,"endianness":"middle"}))).is_err());
+        assert!(Parser::new(serde_json::json!({"id":"Status","type":"object","required":[],"properties":{},"endianness":"native"})).is_err());
+    }
+    #[test]
+    fn test_number_sizes(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/number_size_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"Attitude":{"q":[0.5,-0.25],"temperature":21.5}});
+        let (encoded_message,warnings) = parser.encode_with_warnings(message.clone(),&Options::default()).unwrap();
+        assert_eq!(encoded_message,[&[0][..],&0.5_f32.to_le_bytes(),&(-0.25_f32).to_le_bytes(),&[0x60,0x4D]].concat());
+        assert!(warnings.is_empty());
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"Attitude":{"q":[0.1,0.0],"temperature":21.55}});
+        let (encoded_message,warnings) = parser.encode_with_warnings(message,&Options::default()).unwrap();
+        assert_eq!(warnings.iter().map(|x| x.path.as_str()).collect::<Vec<&str>>(),["Attitude/q/0","Attitude/temperature"]);
+        let decoded_message = parser.decode(encoded_message).unwrap();
+        assert_eq!(decoded_message["Attitude"]["q"][0],0.1_f32 as f64);
+        assert_eq!(decoded_message["Attitude"]["temperature"],21.546875);
+    }
+    #[test]
+    fn test_number_size_limits(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/number_size_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"Attitude":{"q":[0.0,0.0],"temperature":70000.0}})).unwrap_err();
+        assert_eq!(error.path(),"Attitude/temperature");
+        let error = parser.encode(serde_json::json!({"Attitude":{"q":[1e39,0.0],"temperature":0.0}})).unwrap_err();
+        assert_eq!(error.path(),"Attitude/q/0");
+        for (temperature,bits) in [(65504.0,0x7BFF_u16),(-2.0,0xC000),(6.103515625e-5,0x0400),(5.960464477539063e-8,0x0001),(0.0,0x0000)]{
+            let encoded_message = parser.encode(serde_json::json!({"Attitude":{"q":[0.0,0.0],"temperature":temperature}})).unwrap();
+            assert_eq!(encoded_message[9..],bits.to_le_bytes());
+            assert_eq!(parser.decode(encoded_message).unwrap()["Attitude"]["temperature"],temperature);
+        }
+        assert!(parser.encode(serde_json::json!({"Attitude":{"q":[0.0,0.0],"temperature":65520.0}})).is_err());
+        let (_,warnings) = parser.encode_with_warnings(serde_json::json!({"Attitude":{"q":[0.0,0.0],"temperature":1e-9}}),&Options::default()).unwrap();
+        assert_eq!(warnings.len(),1);
+        assert!(matches!(parser.decode(vec![0,0,0,0,0,0,0,0,0,0x00,0x7C]),Err(Error::DecodeError { kind: DecodeErrorKind::InvalidNumber, .. })));
+        let schema = serde_json::json!({"id":"Status","type":"object","required":["value"],"properties":{"value":{"type":"number","size":24}}});
+        assert!(Parser::new(schema).is_err());
+    }
+    #[test]
+    fn test_varint_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/varint_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"counter":300,"offset":-2,"name":"ab","ids":[1,128]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0xAC,0x02,0x03,0x02,b'a',b'b',0x02,0x01,0x80,0x01]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let message = serde_json::json!({"counter":u64::MAX,"offset":i32::MIN,"name":"a".repeat(300),"ids":[]});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message.len(),10+5+2+300+1);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_varint_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/varint_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"counter":0,"offset":1_i64 << 31,"name":"","ids":[]})).unwrap_err();
+        assert_eq!(error.path(),"offset");
+        let message = vec![0x00,0x80,0x80,0x80,0x80,0x10,0x00,0x00];
+        assert_eq!(parser.decode(message),Err(Error::DecodeError { kind: DecodeErrorKind::InvalidVarint, path: "offset".to_string(), offset: 1 }));
+        assert!(matches!(parser.decode(vec![0x80]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"integer","encoding":"zigzag"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"string","lengthEncoding":"varint","lengthSize":16}))).is_err());
+    }
+    #[test]
+    fn test_time_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/time_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"onboard":"2024-03-01T12:00:00.5Z","beacon":1700000000,"ground":"2024-03-01T12:00:00.00025Z"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x7C,0x74,0x21,0xC0,0x80,0x00,0x65,0x53,0xF1,0x00,0x5E,0x66,0x02,0x93,0x2E,0x00,0x00,0xFA]);
+        assert_eq!(parser.decode(encoded_message.clone()).unwrap(),message);
+        let unix_message = serde_json::json!({"onboard":1709294400.5,"beacon":"2023-11-14T22:13:20Z","ground":"2024-03-01T13:00:00.00025+01:00"});
+        assert_eq!(parser.encode(unix_message).unwrap(),encoded_message);
+    }
+    #[test]
+    fn test_time_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/time_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"onboard":"1957-12-31T23:59:59Z","beacon":0,"ground":0})).unwrap_err();
+        assert_eq!(error.path(),"onboard");
+        let error = parser.encode(serde_json::json!({"onboard":0,"beacon":"2106-02-07T06:28:16Z","ground":0})).unwrap_err();
+        assert_eq!(error.path(),"beacon");
+        let error = parser.encode(serde_json::json!({"onboard":"2024-03-01","beacon":0,"ground":0})).unwrap_err();
+        assert_eq!(error.path(),"onboard");
+        let message = vec![0,0,0,0,0,0,0,0,0,0,0,0,0x05,0x26,0x5C,0x00,0,0];
+        assert_eq!(parser.decode(message),Err(Error::DecodeError { kind: DecodeErrorKind::InvalidTime, path: "ground".to_string(), offset: 10 }));
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cuc","coarse":5}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","epoch":"2000-01-01"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","epoch":"9000000000000000000-01-01T00:00:00Z"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","epoch":"10000-01-01T00:00:00Z"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","epoch":"2000-01-01T00:00:00+9000000000000000:00"}))).is_err());
+        //Every time the time code can hold decodes to a value that can be encoded again, even past the year 9999
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","days":3,"epoch":"9999-12-31T00:00:00Z"}))).unwrap();
+        let frame = vec![0xFF,0xFF,0xFF,0x05,0x26,0x5B,0xFF];
+        let decoded_message = parser.decode(frame.clone()).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"field":"55934-06-15T23:59:59.999Z"}));
+        assert_eq!(parser.encode(decoded_message).unwrap(),frame);
+        //Out of range input is an error rather than an overflow
+        let parser = Parser::new(single_field_schema(serde_json::json!({"type":"time","format":"cds","days":3}))).unwrap();
+        for time in [serde_json::json!(1e300),serde_json::json!(-1e300),serde_json::json!("9000000000000000000-01-01T00:00:00Z"),
+            serde_json::json!("2024-03-01T12:00:00+9000000000000000:00"),serde_json::json!("2024-9000000000000000000-01T12:00:00Z")]{
+            let error = parser.encode(serde_json::json!({"field":time})).unwrap_err();
+            assert!(matches!(error,Error::EncodeError { .. }));
+            assert_eq!(error.path(),"field");
+        }
+    }
+    #[test]
+    fn test_conditional_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/conditional_test.json").expect("Could not read schema")).unwrap();
+        for (message,expected_message) in [
+            (serde_json::json!({"mode":"Imaging","exposure":500,"gain":3}),vec![1,0xF4,0x01,3]),
+            (serde_json::json!({"mode":"Downlink","rate":9}),vec![2,9]),
+            (serde_json::json!({"mode":"Idle"}),vec![0]),
+        ]{
+            let encoded_message = parser.encode(message.clone()).unwrap();
+            assert_eq!(encoded_message,expected_message);
+            assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        }
+    }
+    #[test]
+    fn test_conditional_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/conditional_test.json").expect("Could not read schema")).unwrap();
+        let error = parser.encode(serde_json::json!({"mode":"Idle","exposure":500})).unwrap_err();
+        assert_eq!(error.path(),"exposure");
+        let error = parser.encode(serde_json::json!({"mode":"Imaging","gain":3})).unwrap_err();
+        assert_eq!(error.path(),"exposure");
+        assert!(matches!(parser.decode(vec![1,0xF4]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+        assert!(matches!(parser.decode(vec![0,9]),Err(Error::DecodeError { kind: DecodeErrorKind::TrailingBytes(1), .. })));
+        let schema = |condition:Value,then:Value| serde_json::json!({"id":"Payload","type":"object","required":["mode"],"properties":{"mode":{"enum":["Idle","Imaging"]}},"if":condition,"then":then});
+        let then = serde_json::json!({"required":["gain"],"properties":{"gain":{"type":"integer","size":8}}});
+        let error = Parser::new(schema(serde_json::json!({"properties":{"state":{"const":"Imaging"}}}),then.clone())).err().unwrap();
+        assert_eq!(error.path(),"if/state");
+        assert!(Parser::new(schema(serde_json::json!({"properties":{"mode":{"minimum":1}}}),then.clone())).is_err());
+        let then = serde_json::json!({"required":["mode"],"properties":{"mode":{"type":"integer","size":8}}});
+        assert!(Parser::new(schema(serde_json::json!({"properties":{"mode":{"const":"Imaging"}}}),then.clone())).is_err());
+        let schema = serde_json::json!({"id":"Payload","type":"object","required":[],"properties":{},"then":then});
+        assert!(Parser::new(schema).is_err());
+        //Hidden parameters aren't part of messages, so conditions on them could never hold
+        let schema = |field:Value| serde_json::json!({"id":"Payload","type":"object","required":["v"],"properties":{"v":field},
+            "if":{"properties":{"v":{"const":1}}},"then":{"required":["x"],"properties":{"x":{"type":"integer","size":8}}}});
+        assert_eq!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"const":1}))).err().unwrap().path(),"if/v");
+        assert!(Parser::new(schema(serde_json::json!({"type":"checksum","algorithm":"xor"}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"enum":["Idle","Imaging"]}))).is_err());
+    }
+    #[test]
+    fn test_numeric_conditions(){
+        let schema = |field:Value,condition:Value| serde_json::json!({"id":"Payload","type":"object","required":["v"],"properties":{"v":field},
+            "if":{"properties":{"v":condition}},"then":{"required":["x"],"properties":{"x":{"type":"integer","size":8}}}});
+        for (field,condition,encoded) in [
+            (serde_json::json!({"type":"decimal","size":8,"factor":0}),serde_json::json!({"const":1}),vec![1,9]),
+            (serde_json::json!({"type":"decimal","size":8,"factor":1}),serde_json::json!({"const":0.5}),vec![5,9]),
+            (serde_json::json!({"type":"number","size":32}),serde_json::json!({"enum":[1,2]}),vec![0,0,0x80,0x3F,9]),
+            (serde_json::json!({"type":"integer","size":8,"calibration":{"scale":2}}),serde_json::json!({"const":2}),vec![1,9]),
+        ]{
+            let parser = Parser::new(schema(field,condition.clone())).unwrap();
+            let value = condition.get("const").unwrap_or(&condition["enum"][0]).clone();
+            let encoded_message = parser.encode(serde_json::json!({"v":value,"x":9})).unwrap();
+            assert_eq!(encoded_message,encoded);
+            assert_eq!(parser.decode(encoded_message).unwrap()["x"],9);
+        }
+        //Conditions on calibrated integers hold for the raw value when the raw_values option is set
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":8,"calibration":{"scale":2}}),serde_json::json!({"const":2}))).unwrap();
+        let options = Options { raw_values: true, ..Default::default() };
+        assert_eq!(parser.decode_with_options(vec![1,9],&options).unwrap(),serde_json::json!({"v":1,"x":9}));
+        assert_eq!(parser.encode_with_options(serde_json::json!({"v":1,"x":9}),&options).unwrap(),[1,9]);
+        assert_eq!(parser.decode(vec![2]).unwrap(),serde_json::json!({"v":4.0}));
+        //Values are compared as they are sent, so a value that rounds to the condition also selects the parameters
+        let parser = Parser::new(schema(serde_json::json!({"type":"decimal","size":8,"factor":0}),serde_json::json!({"const":1}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"v":1.4,"x":9})).unwrap(),[1,9]);
+    }
+    #[test]
+    fn test_const_and_padding(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/const_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"counter":7,"status":"Safe"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x1A,0xCF,0x02,7,0,0,1]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        assert_eq!(parser.decode(vec![0x1A,0xCF,0xF2,7,0xFF,0xFF,1]).unwrap(),message);
+        assert!(parser.encode(serde_json::json!({"sync":6863,"counter":7,"status":"Safe"})).is_ok());
+        let error = parser.encode(serde_json::json!({"sync":1,"counter":7,"status":"Safe"})).unwrap_err();
+        assert_eq!(error.path(),"sync");
+        let schema = serde_json::json!({"id":"Frame","type":"object","required":["version"],"properties":{"version":{"type":"number","size":32,"const":1}}});
+        let parser = Parser::new(schema).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"version":1})).unwrap(),[0,0,0x80,0x3F]);
+        assert!(parser.encode(serde_json::json!({"version":1.0})).is_ok());
+        assert_eq!(parser.encode(serde_json::json!({"version":2})).unwrap_err().path(),"version");
+        assert_eq!(parser.decode(vec![0,0,0x80,0x3F]).unwrap(),serde_json::json!({}));
+    }
+    #[test]
+    fn test_const_mismatch(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/const_test.json").expect("Could not read schema")).unwrap();
+        let frame = vec![0x1A,0xCF,0x03,7,0,0,1];
+        assert_eq!(parser.decode(frame.clone()),Err(Error::DecodeError { kind: DecodeErrorKind::ConstMismatch(Value::from(3)), path: "version".to_string(), offset: 2 }));
+        let options = Options { lenient_constants: true, ..Default::default() };
+        let (decoded_message,warnings) = parser.decode_with_warnings(frame,&options).unwrap();
+        assert_eq!(decoded_message,serde_json::json!({"counter":7,"status":"Safe"}));
+        assert_eq!(warnings.len(),1);
+        assert_eq!(warnings[0].path,"version");
+        let options = Options { raw_values: true, ..Default::default() };
+        let message = serde_json::json!({"counter":7,"status":"Safe"});
+        let encoded_message = parser.encode_with_options(message.clone(),&options).unwrap();
+        assert_eq!(encoded_message,[0x1A,0xCF,0x02,7,0,0,1]);
+        assert_eq!(parser.decode_with_options(encoded_message,&options).unwrap(),message);
+        let schema = |field:Value,required:Value| serde_json::json!({"id":"Frame","type":"object","required":required,"properties":{"field":field}});
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":4,"const":16}),serde_json::json!(["field"]))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"const":10,"calibration":{"scale":2}}),serde_json::json!(["field"]))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":4,"const":1}),serde_json::json!([]))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"padding"}),serde_json::json!(["field"]))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"array","length":2,"items":{"type":"padding","size":8}}),serde_json::json!(["field"]))).is_err());
+    }
+    #[test]
+    fn test_length_and_count(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/length_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"samples":[1,2,3],"payload":{"id":5,"note":"hi"},"data":"beef"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[3,0,4,1,2,3,5,2,0x68,0x69,2,0xBE,0xEF]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        let empty = serde_json::json!({"samples":[],"payload":{"id":5,"note":""},"data":""});
+        assert_eq!(parser.encode(empty.clone()).unwrap(),[0,0,2,5,0,0]);
+        assert_eq!(parser.decode(vec![0,0,2,5,0,0]).unwrap(),empty);
+        assert!(parser.encode(serde_json::json!({"count":3,"length":4,"samples":[1,2,3],"payload":{"id":5,"note":"hi"},"data":"beef"})).is_ok());
+        assert_eq!(parser.encode(serde_json::json!({"count":2,"samples":[1,2,3],"payload":{"id":5,"note":"hi"},"data":"beef"})).unwrap_err().path(),"count");
+        assert_eq!(parser.encode(serde_json::json!({"length":3,"samples":[1,2,3],"payload":{"id":5,"note":"hi"},"data":"beef"})).unwrap_err().path(),"length");
+        let data = Value::from(vec![0;256]);
+        assert_eq!(parser.encode(serde_json::json!({"samples":[],"payload":{"id":5,"note":""},"data":data})).unwrap_err().path(),"dataLength");
+    }
+    #[test]
+    fn test_length_mismatch(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/length_test.json").expect("Could not read schema")).unwrap();
+        let frame = vec![3,0,5,1,2,3,5,2,0x68,0x69,0,2,0xBE,0xEF];
+        assert_eq!(parser.decode(frame),Err(Error::DecodeError { kind: DecodeErrorKind::LengthMismatch(1), path: "payload".to_string(), offset: 6 }));
+        let error = parser.decode(vec![3,0,3,1,2,3,5,2,0x68,0x69,2,0xBE,0xEF]).unwrap_err();
+        assert!(matches!(error,Error::DecodeError { kind: DecodeErrorKind::Truncated, .. }));
+        assert_eq!(error.path(),"payload/note");
+        assert!(matches!(parser.decode(vec![200,0,4,1,2,3,5,2,0x68,0x69,2,0xBE,0xEF]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+        assert!(matches!(parser.decode(vec![3,0,4,1,2,3,5,2,0x68,0x69,3,0xBE,0xEF]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+        let schema = |count:Value,target:Value| serde_json::json!({"id":"Frame","type":"object","required":["count","target"],"properties":{"count":count,"target":target}});
+        let array = serde_json::json!({"type":"array","items":{"type":"integer","size":8}});
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"target"}),array.clone())).is_ok());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","encoding":"varint","countOf":"target"}),array.clone())).is_ok());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"count"}),array.clone())).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"missing"}),array.clone())).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"signed":true,"countOf":"target"}),array.clone())).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"target","lengthOf":"target"}),array.clone())).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","encoding":"varint","lengthOf":"target"}),array.clone())).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"target"}),serde_json::json!({"type":"string","lengthSize":16}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"integer","size":8,"countOf":"target"}),serde_json::json!({"type":"integer","size":8}))).is_err());
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":8,"lengthOf":"target"}),serde_json::json!({"type":"integer","size":4}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"target":1})).unwrap_err().path(),"count");
+    }
+    #[test]
+    fn test_checksum_round_trip(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/checksum_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"apid":291,"counter":7,"text":"hi"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[0x01,0x23,7,2,0x68,0x69,0xED,0x97]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+        //The length is filled in after the data is sent, but the checksum still covers its final value
+        let schema = serde_json::json!({"id":"Frame","type":"object","required":["header","length","data","check"],"properties":{
+            "header":{"type":"integer","size":8},
+            "length":{"type":"integer","size":8,"lengthOf":"data"},
+            "data":{"type":"blob"},
+            "check":{"type":"checksum","algorithm":"crc32","start":1}
+        }});
+        let parser = Parser::new(schema).unwrap();
+        let message = serde_json::json!({"header":5,"data":"6869"});
+        let encoded_message = parser.encode(message.clone()).unwrap();
+        assert_eq!(encoded_message,[5,2,0x68,0x69,0x2F,0x35,0x8F,0x65]);
+        assert_eq!(parser.decode(encoded_message).unwrap(),message);
+    }
+    #[test]
+    fn test_checksum_errors(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/checksum_test.json").expect("Could not read schema")).unwrap();
+        let frame = vec![0x01,0x23,7,2,0x68,0x68,0xED,0x97];
+        let error = parser.decode(frame).unwrap_err();
+        assert!(matches!(error,Error::DecodeError { kind: DecodeErrorKind::ChecksumMismatch { received: 0xED97, .. }, offset: 6, .. }));
+        assert_eq!(error.path(),"crc");
+        assert!(matches!(parser.decode(vec![0x01,0x23,7,2,0x68,0x69,0xED]),Err(Error::DecodeError { kind: DecodeErrorKind::Truncated, .. })));
+        let schema = |first:Value,check:Value| serde_json::json!({"id":"Frame","type":"object","required":["first","check"],"properties":{"first":first,"check":check}});
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":16}),serde_json::json!({"type":"checksum","algorithm":"fletcher16"}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"first":0x6261})).unwrap(),[0x61,0x62,0xC3,0x25]);
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":8}),serde_json::json!({"type":"checksum","algorithm":"xor","start":2}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"first":1})).unwrap_err().path(),"check");
+        let parser = Parser::new(schema(serde_json::json!({"type":"boolean","size":1}),serde_json::json!({"type":"checksum","algorithm":"xor"}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"first":true})).unwrap_err().path(),"check");
+        assert!(Parser::new(schema(serde_json::json!({"type":"boolean"}),serde_json::json!({"type":"checksum"}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"boolean"}),serde_json::json!({"type":"checksum","algorithm":"md5"}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"boolean"}),serde_json::json!({"type":"checksum","algorithm":"xor","start":-1}))).is_err());
+        assert!(Parser::new(schema(serde_json::json!({"type":"boolean"}),serde_json::json!({"type":"checksum","algorithm":"xor","start":2,"end":1}))).is_err());
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":24}),serde_json::json!({"type":"checksum","algorithm":"xor","start":1,"end":2}))).unwrap();
+        let frame = parser.encode(serde_json::json!({"first":0x123456})).unwrap();
+        assert_eq!(frame,[0x56,0x34,0x12,0x34]);
+        assert_eq!(parser.decode(frame).unwrap(),serde_json::json!({"first":0x123456}));
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":8}),serde_json::json!({"type":"checksum","algorithm":"xor","end":2}))).unwrap();
+        assert_eq!(parser.encode(serde_json::json!({"first":1})).unwrap_err().path(),"check");
+        let error = parser.decode(vec![1,1]).unwrap_err();
+        assert!(matches!(error,Error::DecodeError { kind: DecodeErrorKind::ChecksumRange, offset: 1, .. }));
+        assert_eq!(error.path(),"check");
+        let parser = Parser::new(schema(serde_json::json!({"type":"integer","size":8}),serde_json::json!({"type":"checksum","algorithm":"xor","start":2}))).unwrap();
+        assert!(matches!(parser.decode(vec![1,0]),Err(Error::DecodeError { kind: DecodeErrorKind::ChecksumRange, .. })));
+    }
+    #[test]
+    fn test_limits_on_encode(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/limits_test.json").expect("Could not read schema")).unwrap();
+        let message = serde_json::json!({"temperature":-40.,"voltage":3.5,"current":0.15,"step":8});
+        assert_eq!(parser.decode(parser.encode(message.clone()).unwrap()).unwrap(),message);
+        for (name,value) in [("temperature",-40.5),("temperature",85.5),("voltage",0.),("voltage",10.),("current",0.12),("step",6.)]{
+            let mut invalid = message.clone();
+            invalid[name] = Value::from(value);
+            let error = parser.encode(invalid).unwrap_err();
+            assert!(matches!(error,Error::EncodeError { .. }));
+            assert_eq!(error.path(),name);
+        }
+        //Limits apply to the engineering value, so raw values of calibrated integers aren't checked
+        let options = Options { raw_values: true, ..Default::default() };
+        assert!(parser.encode_with_options(serde_json::json!({"temperature":-100,"voltage":3.5,"current":0.15,"step":8}),&options).is_ok());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"integer","size":8,"minimum":"1"}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"integer","size":8,"multipleOf":0}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"string","maximum":5}))).is_err());
+        assert!(Parser::new(single_field_schema(serde_json::json!({"type":"array","items":{"type":"integer","size":8,"maximum":5}}))).is_ok());
+    }
+    #[test]
+    fn test_limits_on_decode(){
+        let parser = Parser::new_from_string(fs::read_to_string(r"src/test_files/limits_test.json").expect("Could not read schema")).unwrap();
+        let mut frame = parser.encode(serde_json::json!({"temperature":20,"voltage":3.5,"current":0.15,"step":8})).unwrap();
+        frame[0..2].copy_from_slice(&[0xC8,0]);
+        *frame.last_mut().unwrap() = 6;
+        let expected = serde_json::json!({"temperature":100.,"voltage":3.5,"current":0.15,"step":6});
+        assert_eq!(parser.decode(frame.clone()).unwrap(),expected);
+        let (decoded_message,warnings) = parser.decode_with_warnings(frame.clone(),&Options::default()).unwrap();
+        assert_eq!(decoded_message,expected);
+        assert!(warnings.is_empty());
+        let options = Options { check_limits: true, ..Default::default() };
+        let (decoded_message,warnings) = parser.decode_with_warnings(frame,&options).unwrap();
+        assert_eq!(decoded_message,expected);
+        assert_eq!(warnings.iter().map(|x| x.path.as_str()).collect::<Vec<&str>>(),["temperature","step"]);
+    }
+}
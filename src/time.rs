@@ -0,0 +1,261 @@
+//! CCSDS time codes (CCSDS 301.0-B), sent as time parameters
+//!
+//! Times are handled internally as nanoseconds since the Unix epoch, and appear in messages either as ISO-8601 strings in
+//! UTC (`2024-03-01T12:00:00.5Z`) or as Unix seconds. Both forms are accepted when encoding. Leap seconds are not counted,
+//! so a time code with a TAI epoch such as the default 1958-01-01 is treated as if it counted UTC seconds.
+
+use serde_json::{Map, Value};
+
+use crate::{wire::{Endianness, Reader, Writer}, DecodeErrorKind, Error};
+
+const NANOS_PER_SECOND:i128 = 1_000_000_000;
+const NANOS_PER_DAY:i128 = 86_400*NANOS_PER_SECOND;
+///The CCSDS epoch of 1958-01-01, in nanoseconds since the Unix epoch
+const CCSDS_EPOCH:i128 = -4_383*NANOS_PER_DAY;
+///Largest number of Unix seconds accepted in messages, beyond the range of any time code (a 3 byte day count reaches
+///about 46,000 years) while keeping the arithmetic on nanoseconds far from overflowing
+const MAX_SECONDS:f64 = 1e13;
+///Start of the year 10000 in nanoseconds since the Unix epoch, the limit for epochs so that a 3 byte day count from the
+///epoch stays within the five digit years accepted by [parse_iso]
+const LAST_EPOCH:i128 = 2_932_897*NANOS_PER_DAY;
+
+///Layout of a time code, with sizes in bytes
+#[derive(Clone, Copy, Debug)]
+enum TimeCode{
+    ///Unsegmented time code, a count of seconds followed by a binary fraction of a second
+    Cuc{coarse:u32,fine:u32},
+    ///Day segmented time code, a count of days followed by the milliseconds of the day and optionally the microseconds
+    ///of the millisecond
+    Cds{days:u32,submilliseconds:u32},
+}
+
+///How times appear in messages
+#[derive(Clone, Copy, Debug)]
+enum Representation{
+    Iso,
+    Unix,
+}
+
+///Compiled time parameter
+#[derive(Clone, Debug)]
+pub(crate) struct Time{
+    code:TimeCode,
+    ///Start of the time code in nanoseconds since the Unix epoch
+    epoch:i128,
+    representation:Representation,
+}
+
+impl Time{
+    ///Compiles the format, coarse, fine, days, submilliseconds, epoch and representation keywords of a time parameter
+    pub(crate) fn new(definition:&Map<String,Value>)->Result<Time,Error>{
+        let octets = |keyword:&str,default:u64,range:&[u64]|->Result<u32,Error>{
+            match definition.get(keyword).map(|x| x.as_u64()){
+                None => Ok(default as u32),
+                Some(Some(size)) if range.contains(&size) => Ok(size as u32),
+                _ => Err(Error::schema(&format!("{} field must be one of {:?} bytes",keyword,range))),
+            }
+        };
+        let code = match definition.get("format").and_then(|x| x.as_str()){
+            Some("cuc") => TimeCode::Cuc { coarse: octets("coarse",4,&[1,2,3,4])?, fine: octets("fine",0,&[0,1,2,3])? },
+            Some("cds") => TimeCode::Cds { days: octets("days",2,&[2,3])?, submilliseconds: octets("submilliseconds",0,&[0,2])? },
+            _ => return Err(Error::schema("Time parameters must have a format field of either cuc or cds")),
+        };
+        let epoch = match definition.get("epoch"){
+            Some(data) => match data.as_str().and_then(parse_iso){
+                Some(epoch) if epoch < LAST_EPOCH => epoch,
+                Some(_) => return Err(Error::schema("Epoch field must be before the year 10000")),
+                None => return Err(Error::schema("Epoch field must be an ISO-8601 time such as 1958-01-01T00:00:00Z")),
+            },
+            None => CCSDS_EPOCH,
+        };
+        let representation = match definition.get("representation").map(|x| x.as_str()){
+            None | Some(Some("iso")) => Representation::Iso,
+            Some(Some("unix")) => Representation::Unix,
+            _ => return Err(Error::schema("Representation field must be either iso or unix")),
+        };
+        Ok(Time { code, epoch, representation })
+    }
+
+    ///Sends the time given in the message, with each field of the time code in the given byte order
+    pub(crate) fn encode(&self,value:&Value,output:&mut Writer,endianness:Option<Endianness>)->Result<(),Error>{
+        let time = match value{
+            Value::String(data) => match parse_iso(data){
+                Some(time) => time,
+                None => return Err(Error::encode("Time string is not an ISO-8601 time such as 2024-03-01T12:00:00Z")),
+            },
+            Value::Number(data) => match data.as_f64(){
+                Some(seconds) if seconds.abs() <= MAX_SECONDS => (seconds*1e9).round() as i128,
+                Some(seconds) if seconds.is_finite() => return Err(Error::encode("Time is outside of the range of the time code")),
+                _ => return Err(Error::encode("Time could not be serialized as Unix seconds")),
+            },
+            _ => return Err(Error::encode("Time must be an ISO-8601 string or a number of Unix seconds")),
+        };
+        let elapsed = time-self.epoch;
+        let out_of_range = || Error::encode("Time is outside of the range of the time code");
+        if elapsed < 0{
+            return Err(out_of_range())
+        }
+        match self.code{
+            TimeCode::Cuc { coarse, fine } => {
+                let bits = 8*(coarse+fine);
+                let ticks = divide_rounded(elapsed << (8*fine),NANOS_PER_SECOND);
+                if ticks >= 1 << bits{
+                    return Err(out_of_range())
+                }
+                output.write_value(ticks as u64,bits,endianness);
+            },
+            TimeCode::Cds { days, submilliseconds } => {
+                let resolution = if submilliseconds == 0{ 1_000_000 } else { 1_000 };
+                let elapsed = divide_rounded(elapsed,resolution)*resolution;
+                let day = elapsed/NANOS_PER_DAY;
+                if day >= 1 << (8*days){
+                    return Err(out_of_range())
+                }
+                let nanoseconds = elapsed%NANOS_PER_DAY;
+                output.write_value(day as u64,8*days,endianness);
+                output.write_value((nanoseconds/1_000_000) as u64,32,endianness);
+                if submilliseconds != 0{
+                    output.write_value((nanoseconds%1_000_000/1_000) as u64,16,endianness);
+                }
+            },
+        }
+        Ok(())
+    }
+
+    ///Reads a time code, with each field in the given byte order
+    pub(crate) fn decode(&self,message:&mut Reader,endianness:Option<Endianness>)->Result<Value,DecodeErrorKind>{
+        let elapsed = match self.code{
+            TimeCode::Cuc { coarse, fine } => {
+                let ticks = message.read_value(8*(coarse+fine),endianness)? as i128;
+                divide_rounded(ticks*NANOS_PER_SECOND,1 << (8*fine))
+            },
+            TimeCode::Cds { days, submilliseconds } => {
+                let day = message.read_value(8*days,endianness)? as i128;
+                let milliseconds = message.read_value(32,endianness)? as i128;
+                let microseconds = match submilliseconds{
+                    0 => 0,
+                    _ => message.read_value(16,endianness)? as i128,
+                };
+                if milliseconds >= 86_400_000 || microseconds >= 1_000{
+                    return Err(DecodeErrorKind::InvalidTime)
+                }
+                day*NANOS_PER_DAY+milliseconds*1_000_000+microseconds*1_000
+            },
+        };
+        let time = self.epoch+elapsed;
+        Ok(match self.representation{
+            Representation::Iso => Value::String(format_iso(time)),
+            //Whole seconds stay integers when the time code has no fraction of a second
+            Representation::Unix => match self.code{
+                TimeCode::Cuc { fine: 0, .. } => Value::from(time.div_euclid(NANOS_PER_SECOND) as i64),
+                _ => Value::from(time as f64/1e9),
+            },
+        })
+    }
+}
+
+///Integer division rounding to the nearest integer, for a positive divisor
+fn divide_rounded(value:i128,divisor:i128)->i128{
+    (value+divisor/2).div_euclid(divisor)
+}
+
+///Days since the Unix epoch of a date in the proleptic Gregorian calendar
+fn days_from_civil(year:i64,month:i64,day:i64)->i64{
+    let year = if month <= 2{ year-1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year-era*400;
+    let day_of_year = (153*((month+9)%12)+2)/5+day-1;
+    let day_of_era = year_of_era*365+year_of_era/4-year_of_era/100+day_of_year;
+    era*146_097+day_of_era-719_468
+}
+
+///Date in the proleptic Gregorian calendar of a number of days since the Unix epoch
+fn civil_from_days(days:i64)->(i64,i64,i64){
+    let days = days+719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days-era*146_097;
+    let year_of_era = (day_of_era-day_of_era/1_460+day_of_era/36_524-day_of_era/146_096)/365;
+    let day_of_year = day_of_era-(365*year_of_era+year_of_era/4-year_of_era/100);
+    let shifted_month = (5*day_of_year+2)/153;
+    let day = day_of_year-(153*shifted_month+2)/5+1;
+    let month = if shifted_month < 10{ shifted_month+3 } else { shifted_month-9 };
+    (if month <= 2{ era*400+year_of_era+1 } else { era*400+year_of_era },month,day)
+}
+
+///Parses an ISO-8601 time of the form `YYYY-MM-DDTHH:MM:SS[.fraction]` followed by `Z` or a `+HH:MM`/`-HH:MM` offset,
+///to nanoseconds since the Unix epoch. Years are limited to 0 to 99999, which covers every time a time code can hold from
+///an epoch up to the year 9999 (so that decoded times can always be encoded again) and keeps the date arithmetic from
+///overflowing
+fn parse_iso(text:&str)->Option<i128>{
+    let number = |part:&str| -> Option<i64>{
+        if part.is_empty() || !part.bytes().all(|x| x.is_ascii_digit()){
+            return None
+        }
+        part.parse().ok()
+    };
+    let (date,rest) = text.split_once('T')?;
+    let mut date_parts = date.splitn(3,'-');
+    let (year,month,day) = (number(date_parts.next()?)?,number(date_parts.next()?)?,number(date_parts.next()?)?);
+    if year > 99_999 || !(1..=12).contains(&month) || !(1..=31).contains(&day){
+        return None
+    }
+    let (clock,offset) = match rest.strip_suffix('Z'){
+        Some(clock) => (clock,0),
+        None => {
+            let split = rest.rfind(['+','-'])?;
+            let (hours,minutes) = rest[split+1..].split_once(':')?;
+            let (hours,minutes) = (number(hours)?,number(minutes)?);
+            if hours > 23 || minutes > 59{
+                return None
+            }
+            let offset = hours*3_600+minutes*60;
+            (&rest[..split],if rest[split..].starts_with('-'){ -offset } else { offset })
+        },
+    };
+    let (clock,fraction) = match clock.split_once('.'){
+        Some((clock,fraction)) if fraction.len() <= 9 => (clock,number(fraction)?*10_i64.pow(9-fraction.len() as u32)),
+        Some(_) => return None,
+        None => (clock,0),
+    };
+    let mut clock_parts = clock.splitn(3,':');
+    let (hour,minute,second) = (number(clock_parts.next()?)?,number(clock_parts.next()?)?,number(clock_parts.next()?)?);
+    let days = days_from_civil(year,month,day);
+    if civil_from_days(days) != (year,month,day) || hour > 23 || minute > 59 || second > 59{
+        return None
+    }
+    let seconds = days*86_400+hour*3_600+minute*60+second-offset;
+    Some(seconds as i128*NANOS_PER_SECOND+fraction as i128)
+}
+
+///Formats nanoseconds since the Unix epoch as an ISO-8601 time in UTC, with as many fractional digits as needed
+fn format_iso(time:i128)->String{
+    let seconds = time.div_euclid(NANOS_PER_SECOND) as i64;
+    let nanoseconds = time.rem_euclid(NANOS_PER_SECOND);
+    let (year,month,day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    let mut output = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",year,month,day,second_of_day/3_600,second_of_day/60%60,second_of_day%60);
+    if nanoseconds != 0{
+        output.push_str(format!(".{:09}",nanoseconds).trim_end_matches('0'));
+    }
+    output.push('Z');
+    output
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    #[test]
+    fn test_iso_round_trip(){
+        for (text,time) in [("1970-01-01T00:00:00Z",0),("1958-01-01T00:00:00Z",CCSDS_EPOCH),("2000-02-29T23:59:59.5Z",951_868_799_500_000_000)]{
+            assert_eq!(parse_iso(text),Some(time));
+            assert_eq!(format_iso(time),text);
+        }
+        assert_eq!(parse_iso("2024-03-01T14:00:00+02:00"),parse_iso("2024-03-01T12:00:00Z"));
+        assert_eq!(parse_iso("2024-03-01T10:30:00-01:30"),parse_iso("2024-03-01T12:00:00Z"));
+        assert_eq!(parse_iso("2023-02-29T00:00:00Z"),None);
+        assert_eq!(parse_iso("10000-01-01T00:00:00Z"),Some(LAST_EPOCH));
+        assert_eq!(format_iso(LAST_EPOCH),"10000-01-01T00:00:00Z");
+        assert_eq!(parse_iso("2024-03-01 12:00:00Z"),None);
+        assert_eq!(parse_iso("2024-03-01T12:00:60Z"),None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Bit level access to the frames being encoded and decoded
+//!
+//! Values are packed least significant bit first: the first bit written goes into the lowest bit of the first byte. Fields
+//! made of whole bytes that start on a byte boundary therefore come out exactly as their little endian byte representation,
+//! while smaller fields (such as packed booleans) share bytes with their neighbours. The final byte of a frame is padded
+//! with zero bits.
+//!
+//! Schemas can instead declare a big endian byte order, in which case values are packed most significant bit first: the
+//! first bit written goes into the highest bit of the first byte, and whole-byte fields come out as their big endian bytes.
+//! Parameters that set their own byte order have their bytes swapped when it differs from the order of the frame.
+//!
+//! Every read returns a [DecodeErrorKind::Truncated] error rather than panicking when the frame is shorter than the schema
+//! expects, so corrupted downlink frames can't take down the caller.
+
+use serde_json::Value;
+
+use std::ops::Range;
+
+use crate::{checksum::Checksum, DecodeErrorKind, Error, Warning};
+
+///Byte order of a frame or of a single parameter
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum Endianness{
+    ///Least significant byte first, with bits packed least significant bit first
+    #[default]
+    Little,
+    ///Most significant byte first, with bits packed most significant bit first
+    Big,
+}
+
+impl Endianness{
+    ///Compiles the endianness keyword, which is "little" or "big"
+    pub(crate) fn new(definition:Option<&Value>)->Result<Option<Endianness>,Error>{
+        match definition.map(|x| x.as_str()){
+            None => Ok(None),
+            Some(Some("little")) => Ok(Some(Endianness::Little)),
+            Some(Some("big")) => Ok(Some(Endianness::Big)),
+            _ => Err(Error::schema("Endianness field must be either little or big")),
+        }
+    }
+}
+
+///Reverses the byte order of a value that is a whole number of bytes
+fn swap_bytes(value:u64,bits:u32)->u64{
+    value.swap_bytes() >> (64-bits)
+}
+
+///Bit level output buffer for a frame being encoded
+#[derive(Default)]
+pub(crate) struct Writer{
+    data:Vec<u8>,
+    ///Number of bits written
+    position:usize,
+    order:Endianness,
+    warnings:Vec<Warning>,
+    ///Checksums to fill in once the frame is complete, as the bit position, the algorithm, the bytes covered and the byte
+    ///order
+    checksums:Vec<(usize,Checksum,Range<usize>,Option<Endianness>)>,
+}
+
+impl Writer{
+    pub(crate) fn new(order:Endianness)->Writer{
+        Writer { order, ..Default::default() }
+    }
+    ///Records a warning about the value being written
+    pub(crate) fn warn(&mut self,warning_msg:&str){
+        self.warnings.push(Warning::new(warning_msg));
+    }
+    ///Number of warnings recorded so far, to be passed to [Writer::warnings_within]
+    pub(crate) fn warning_count(&self)->usize{
+        self.warnings.len()
+    }
+    ///Prepends a parameter name to the path of the warnings recorded since the given count
+    pub(crate) fn warnings_within(&mut self,from:usize,segment:&str){
+        let recent = self.warnings.split_off(from);
+        self.warnings.extend(recent.into_iter().map(|x| x.within(segment)));
+    }
+    ///Number of bits written so far, the position to pass to [Writer::patch_value]
+    pub(crate) fn bit_position(&self)->usize{
+        self.position
+    }
+    ///Appends the low `bits` bits of the value, up to 64 bits
+    pub(crate) fn write_bits(&mut self,value:u64,bits:u32){
+        let position = self.position;
+        self.position += bits as usize;
+        self.data.resize(self.position.div_ceil(8),0);
+        self.put_bits(position,value,bits);
+    }
+    ///Sets the bits starting at a bit position within the data written so far, which must still be zero
+    fn put_bits(&mut self,mut position:usize,mut value:u64,mut bits:u32){
+        if bits < 64{
+            value &= (1 << bits)-1;
+        }
+        while bits > 0{
+            let offset = position % 8;
+            let take = bits.min(8-offset as u32);
+            let byte = &mut self.data[position/8];
+            match self.order{
+                Endianness::Little => {
+                    *byte |= ((value & ((1 << take)-1)) as u8) << offset;
+                    value = value.checked_shr(take).unwrap_or(0);
+                },
+                Endianness::Big => {
+                    *byte |= (((value >> (bits-take)) & ((1 << take)-1)) as u8) << (8-offset as u32-take);
+                },
+            }
+            bits -= take;
+            position += take as usize;
+        }
+    }
+    ///Fills in a value that was written as zeros at an earlier bit position, once it is known
+    pub(crate) fn patch_value(&mut self,position:usize,value:u64,bits:u32,endianness:Option<Endianness>){
+        match endianness{
+            Some(endianness) if endianness != self.order && bits.is_multiple_of(8) => self.put_bits(position,swap_bytes(value,bits),bits),
+            _ => self.put_bits(position,value,bits),
+        }
+    }
+    ///Appends a value with the given byte order, which only differs from [Writer::write_bits] for whole-byte values whose
+    ///byte order differs from that of the frame
+    pub(crate) fn write_value(&mut self,value:u64,bits:u32,endianness:Option<Endianness>){
+        match endianness{
+            Some(endianness) if endianness != self.order && bits.is_multiple_of(8) => self.write_bits(swap_bytes(value,bits),bits),
+            _ => self.write_bits(value,bits),
+        }
+    }
+    pub(crate) fn write_bytes(&mut self,data:&[u8]){
+        if self.position.is_multiple_of(8){
+            self.data.extend_from_slice(data);
+            self.position += data.len()*8;
+        } else {
+            for byte in data{
+                self.write_bits(*byte as u64,8);
+            }
+        }
+    }
+    ///Appends an unsigned LEB128 varint: 7 bits of the value per byte starting with the lowest, with the top bit of each
+    ///byte set when another byte follows
+    pub(crate) fn write_varint(&mut self,mut value:u64){
+        while value >= 0x80{
+            self.write_bits((value & 0x7F) | 0x80,8);
+            value >>= 7;
+        }
+        self.write_bits(value,8);
+    }
+    ///Reserves space for a checksum covering the given bytes, which must come before the checksum
+    pub(crate) fn write_checksum(&mut self,checksum:Checksum,covered:Range<usize>,endianness:Option<Endianness>)->Result<(),&'static str>{
+        if !self.position.is_multiple_of(8){
+            return Err("Checksum must start on a byte boundary")
+        }
+        self.checksums.push((self.position,checksum,covered,endianness));
+        self.write_bits(0,checksum.size());
+        Ok(())
+    }
+    ///The encoded frame, with the final byte padded by zero bits and the checksums filled in, along with the warnings
+    ///recorded while encoding it
+    pub(crate) fn into_parts(mut self)->(Vec<u8>,Vec<Warning>){
+        //In order of position, so a checksum covering an earlier checksum sees its final value
+        for (position,checksum,covered,endianness) in std::mem::take(&mut self.checksums){
+            let value = checksum.compute(&self.data[covered]);
+            self.patch_value(position,value,checksum.size(),endianness);
+        }
+        (self.data,self.warnings)
+    }
+}
+
+///Bit level cursor over a frame being decoded
+pub(crate) struct Reader<'a>{
+    data:&'a [u8],
+    ///Number of bits read
+    position:usize,
+    ///Bit position reads are limited to, the end of the frame unless a lengthOf parameter bounds the current parameter
+    end:usize,
+    ///Number of elements given by a countOf parameter, taken by the parameter it counts
+    count:Option<usize>,
+    order:Endianness,
+    warnings:Vec<Warning>,
+}
+
+impl<'a> Reader<'a>{
+    pub(crate) fn new(data:&'a [u8],order:Endianness)->Reader<'a>{
+        Reader { data, position: 0, end: data.len()*8, count: None, order, warnings: Vec::new() }
+    }
+    ///Records a warning about the value being read
+    pub(crate) fn warn(&mut self,warning_msg:&str){
+        self.warnings.push(Warning::new(warning_msg));
+    }
+    ///Number of warnings recorded so far, to be passed to [Reader::warnings_within]
+    pub(crate) fn warning_count(&self)->usize{
+        self.warnings.len()
+    }
+    ///Prepends a parameter name to the path of the warnings recorded since the given count
+    pub(crate) fn warnings_within(&mut self,from:usize,segment:&str){
+        let recent = self.warnings.split_off(from);
+        self.warnings.extend(recent.into_iter().map(|x| x.within(segment)));
+    }
+    ///The warnings recorded while decoding the frame
+    pub(crate) fn into_warnings(self)->Vec<Warning>{
+        self.warnings
+    }
+    ///The whole frame, including the bytes already read
+    pub(crate) fn frame(&self)->&'a [u8]{
+        self.data
+    }
+    ///Byte offset of the next read within the frame
+    pub(crate) fn position(&self)->usize{
+        self.position/8
+    }
+    ///Number of bits not yet consumed
+    pub(crate) fn remaining_bits(&self)->usize{
+        self.end-self.position
+    }
+    ///Limits reads to the next `length` bytes, returning the previous limit to pass to [Reader::unlimit]
+    pub(crate) fn limit(&mut self,length:usize)->Result<usize,DecodeErrorKind>{
+        if length.saturating_mul(8) > self.remaining_bits(){
+            return Err(DecodeErrorKind::Truncated)
+        }
+        let end = self.end;
+        self.end = self.position+length*8;
+        Ok(end)
+    }
+    ///Restores the limit from before [Reader::limit]
+    pub(crate) fn unlimit(&mut self,end:usize){
+        self.end = end;
+    }
+    ///Passes the number of elements of the next parameter, as given by a countOf parameter
+    pub(crate) fn set_count(&mut self,count:usize){
+        self.count = Some(count);
+    }
+    pub(crate) fn take_count(&mut self)->Option<usize>{
+        self.count.take()
+    }
+    ///Takes the next `bits` bits of the frame as an unsigned value, up to 64 bits
+    pub(crate) fn read_bits(&mut self,mut bits:u32)->Result<u64,DecodeErrorKind>{
+        if bits as usize > self.remaining_bits(){
+            return Err(DecodeErrorKind::Truncated)
+        }
+        let mut value:u64 = 0;
+        let mut filled = 0;
+        while bits > 0{
+            let offset = (self.position % 8) as u32;
+            let take = bits.min(8-offset);
+            let byte = self.data[self.position/8];
+            match self.order{
+                Endianness::Little => {
+                    value |= ((byte >> offset) as u64 & ((1 << take)-1)) << filled;
+                    filled += take;
+                },
+                Endianness::Big => value = (value << take) | ((byte >> (8-offset-take)) as u64 & ((1 << take)-1)),
+            }
+            bits -= take;
+            self.position += take as usize;
+        }
+        Ok(value)
+    }
+    ///Takes a value with the given byte order, the counterpart of [Writer::write_value]
+    pub(crate) fn read_value(&mut self,bits:u32,endianness:Option<Endianness>)->Result<u64,DecodeErrorKind>{
+        let value = self.read_bits(bits)?;
+        match endianness{
+            Some(endianness) if endianness != self.order && bits.is_multiple_of(8) => Ok(swap_bytes(value,bits)),
+            _ => Ok(value),
+        }
+    }
+    ///Takes an unsigned LEB128 varint, the counterpart of [Writer::write_varint]
+    pub(crate) fn read_varint(&mut self)->Result<u64,DecodeErrorKind>{
+        let mut value:u64 = 0;
+        for shift in (0..64).step_by(7){
+            let byte = self.read_u8()? as u64;
+            //The tenth byte may only hold the final bit of a 64 bit value
+            if shift == 63 && byte > 1{
+                return Err(DecodeErrorKind::InvalidVarint)
+            }
+            value |= (byte & 0x7F) << shift;
+            if byte & 0x80 == 0{
+                return Ok(value)
+            }
+        }
+        Err(DecodeErrorKind::InvalidVarint)
+    }
+    pub(crate) fn read_u8(&mut self)->Result<u8,DecodeErrorKind>{
+        Ok(self.read_bits(8)? as u8)
+    }
+    ///Takes the next `length` bytes of the frame
+    pub(crate) fn read_bytes(&mut self,length:usize)->Result<Vec<u8>,DecodeErrorKind>{
+        if length.saturating_mul(8) > self.remaining_bits(){
+            return Err(DecodeErrorKind::Truncated)
+        }
+        if self.position.is_multiple_of(8){
+            let start = self.position/8;
+            self.position += length*8;
+            Ok(self.data[start..start+length].to_vec())
+        } else {
+            (0..length).map(|_| self.read_u8()).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    #[test]
+    fn test_bit_round_trip(){
+        let mut writer = Writer::new(Endianness::Little);
+        writer.write_bits(1,1);
+        writer.write_bits(0,1);
+        writer.write_bits(5,3);
+        writer.write_bits(0xABC,12);
+        writer.write_bytes(&[0x12,0x34]);
+        writer.write_bits(u64::MAX,64);
+        let data = writer.into_parts().0;
+        assert_eq!(data.len(),13);
+        assert_eq!(data[0],0b1001_0101);
+        let mut reader = Reader::new(&data,Endianness::Little);
+        assert_eq!(reader.read_bits(1),Ok(1));
+        assert_eq!(reader.read_bits(1),Ok(0));
+        assert_eq!(reader.read_bits(3),Ok(5));
+        assert_eq!(reader.read_bits(12),Ok(0xABC));
+        assert_eq!(reader.read_bytes(2),Ok(vec![0x12,0x34]));
+        assert_eq!(reader.read_bits(64),Ok(u64::MAX));
+        assert_eq!(reader.remaining_bits(),7);
+        assert_eq!(reader.read_bits(8),Err(DecodeErrorKind::Truncated));
+    }
+    #[test]
+    fn test_aligned_bytes_are_little_endian(){
+        let mut writer = Writer::new(Endianness::Little);
+        writer.write_bits(0x1234,16);
+        assert_eq!(writer.into_parts().0,vec![0x34,0x12]);
+    }
+    #[test]
+    fn test_big_endian_round_trip(){
+        let mut writer = Writer::new(Endianness::Big);
+        writer.write_bits(1,1);
+        writer.write_bits(5,3);
+        writer.write_bits(0xABC,12);
+        writer.write_bits(0x1234,16);
+        writer.write_value(0x1234,16,Some(Endianness::Little));
+        writer.write_bits(u64::MAX,64);
+        writer.write_bits(1,1);
+        let data = writer.into_parts().0;
+        assert_eq!(&data[..6],[0b1101_1010,0xBC,0x12,0x34,0x34,0x12]);
+        assert_eq!(data[14],0x80);
+        let mut reader = Reader::new(&data,Endianness::Big);
+        assert_eq!(reader.read_bits(1),Ok(1));
+        assert_eq!(reader.read_bits(3),Ok(5));
+        assert_eq!(reader.read_bits(12),Ok(0xABC));
+        assert_eq!(reader.read_bits(16),Ok(0x1234));
+        assert_eq!(reader.read_value(16,Some(Endianness::Little)),Ok(0x1234));
+        assert_eq!(reader.read_bits(64),Ok(u64::MAX));
+        assert_eq!(reader.read_bits(1),Ok(1));
+    }
+    #[test]
+    fn test_swapped_values(){
+        let mut writer = Writer::new(Endianness::Little);
+        writer.write_value(0x123456,24,Some(Endianness::Big));
+        writer.write_value(0x1234,16,Some(Endianness::Little));
+        let data = writer.into_parts().0;
+        assert_eq!(data,vec![0x12,0x34,0x56,0x34,0x12]);
+        let mut reader = Reader::new(&data,Endianness::Little);
+        assert_eq!(reader.read_value(24,Some(Endianness::Big)),Ok(0x123456));
+        assert_eq!(reader.read_value(16,Some(Endianness::Little)),Ok(0x1234));
+    }
+    #[test]
+    fn test_varint_round_trip(){
+        let mut writer = Writer::new(Endianness::Little);
+        for value in [0,1,127,128,300,u64::MAX]{
+            writer.write_varint(value);
+        }
+        let data = writer.into_parts().0;
+        assert_eq!(&data[..7],[0x00,0x01,0x7F,0x80,0x01,0xAC,0x02]);
+        assert_eq!(data.len(),17);
+        let mut reader = Reader::new(&data,Endianness::Little);
+        for value in [0,1,127,128,300,u64::MAX]{
+            assert_eq!(reader.read_varint(),Ok(value));
+        }
+        assert_eq!(Reader::new(&[0xFF;10],Endianness::Little).read_varint(),Err(DecodeErrorKind::InvalidVarint));
+        assert_eq!(Reader::new(&[0x80],Endianness::Little).read_varint(),Err(DecodeErrorKind::Truncated));
+    }
+    #[test]
+    fn test_patch_and_limit(){
+        for order in [Endianness::Little,Endianness::Big]{
+            let mut writer = Writer::new(order);
+            writer.write_bits(1,4);
+            let position = writer.bit_position();
+            writer.write_bits(0,12);
+            writer.write_bits(0x7,3);
+            writer.patch_value(position,0xABC,12,None);
+            let data = writer.into_parts().0;
+            let mut reader = Reader::new(&data,order);
+            assert_eq!(reader.read_bits(4),Ok(1));
+            assert_eq!(reader.read_bits(12),Ok(0xABC));
+            assert_eq!(reader.limit(2),Err(DecodeErrorKind::Truncated));
+            let end = reader.limit(0).unwrap();
+            assert_eq!(reader.read_bits(3),Err(DecodeErrorKind::Truncated));
+            reader.unlimit(end);
+            assert_eq!(reader.read_bits(3),Ok(0x7));
+        }
+        let mut writer = Writer::new(Endianness::Little);
+        writer.write_bits(0,16);
+        writer.patch_value(0,0x1234,16,Some(Endianness::Big));
+        assert_eq!(writer.into_parts().0,vec![0x12,0x34]);
+    }
+}